@@ -1,6 +1,7 @@
 // AI provider abstraction — supports Anthropic, OpenAI, Azure OpenAI, and Ollama.
 // Configuration is read from environment variables at call time.
 
+use serde::{Deserialize, Serialize};
 use tauri::Emitter;
 
 // ── Provider ──────────────────────────────────────────────────────────────────
@@ -15,11 +16,20 @@ pub enum AiProvider {
 
 // ── Config ────────────────────────────────────────────────────────────────────
 
+/// Response budget sent to Anthropic, which requires one, when the caller
+/// doesn't pass `max_tokens`. Other providers get no limit field at all.
+pub const DEFAULT_MAX_TOKENS: u32 = 4096;
+
 pub struct AiConfig {
-    pub provider:  AiProvider,
-    pub api_key:   Option<String>,
-    pub model:     String,
-    pub base_url:  Option<String>,
+    pub provider:    AiProvider,
+    pub api_key:     Option<String>,
+    pub model:       String,
+    pub base_url:    Option<String>,
+    /// None omits the limit (OpenAI o-series models reject `max_tokens`);
+    /// Anthropic falls back to `DEFAULT_MAX_TOKENS`.
+    pub max_tokens:  Option<u32>,
+    /// None leaves the provider's default temperature in place.
+    pub temperature: Option<f32>,
 }
 
 impl AiConfig {
@@ -85,7 +95,176 @@ impl AiConfig {
         };
         let model = std::env::var("AI_MODEL").unwrap_or_else(|_| default_model.to_string());
 
-        Ok(AiConfig { provider, api_key, model, base_url })
+        Ok(AiConfig {
+            provider,
+            api_key,
+            model,
+            base_url,
+            max_tokens: None,
+            temperature: None,
+        })
+    }
+}
+
+// ── Structured insights ───────────────────────────────────────────────────────
+
+/// One finding of an analysis — mirrors `AIInsight` in src/types/ai.ts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiInsight {
+    /// "critical", "warning" or "suggestion".
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+/// The `{ "insights": [...] }` shape the analysis prompts ask for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsightReport {
+    pub insights: Vec<AiInsight>,
+}
+
+/// Name of the tool / response schema used for structured insights.
+const INSIGHTS_SCHEMA_NAME: &str = "report_insights";
+
+/// JSON schema of `InsightReport`. Every property is required and `command`
+/// is nullable, as OpenAI's strict mode demands.
+fn insights_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "insights": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "type": {
+                            "type": "string",
+                            "enum": ["critical", "warning", "suggestion"]
+                        },
+                        "title":   { "type": "string" },
+                        "body":    { "type": "string" },
+                        "command": { "type": ["string", "null"] }
+                    },
+                    "required": ["type", "title", "body", "command"],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["insights"],
+        "additionalProperties": false
+    })
+}
+
+/// Request fields an error body names when the structured-output part of the
+/// request is what the endpoint rejected.
+const STRUCTURED_FIELDS: &[&str] = &["response_format", "json_schema", "tool_choice", "tools"];
+
+/// Whether an error response means "this endpoint doesn't understand the
+/// structured-output request" — e.g. an OpenAI-compatible server without
+/// `json_schema` — so the caller falls back to prose parsing instead of
+/// failing. Other 4xx errors (bad model name, prompt too long) are returned
+/// as-is rather than paying for a second request that hides them.
+fn structured_unsupported(status: reqwest::StatusCode, body: &str) -> bool {
+    let body = body.to_ascii_lowercase();
+    matches!(status.as_u16(), 400 | 404 | 422)
+        && STRUCTURED_FIELDS.iter().any(|field| body.contains(field))
+}
+
+/// Payload of `ai-parse-error`: why the reply isn't an `InsightReport`, plus
+/// the raw text so the UI can still show it.
+#[derive(Debug, Clone, Serialize)]
+pub struct AiParseError {
+    pub error: String,
+    pub raw: String,
+}
+
+/// Drops commas directly followed (ignoring whitespace) by `}` or `]`,
+/// leaving string contents alone.
+fn strip_trailing_commas(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut out = String::with_capacity(json.len());
+    let (mut in_string, mut escaped) = (false, false);
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            match (escaped, c) {
+                (true, _) => escaped = false,
+                (false, '\\') => escaped = true,
+                (false, '"') => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|n| !n.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Parses a prose reply into an `InsightReport`: takes the body of a
+/// ```` ```json ```` fence if there is one, then the outermost `{...}`, drops
+/// trailing commas, and checks each insight's type.
+pub fn extract_insights(raw: &str) -> Result<InsightReport, String> {
+    let mut text = raw;
+    if let Some(start) = text.find("```") {
+        let body = &text[start + 3..];
+        // Skip the fence's language tag, e.g. "json".
+        let body = body.trim_start_matches(|c: char| c.is_ascii_alphanumeric());
+        text = body.find("```").map_or(body, |end| &body[..end]);
+    }
+    let (Some(start), Some(end)) = (text.find('{'), text.rfind('}')) else {
+        return Err("No JSON object in the response".to_string());
+    };
+    if end < start {
+        return Err("No JSON object in the response".to_string());
+    }
+
+    let json = strip_trailing_commas(&text[start..=end]);
+    let report: InsightReport =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid insights JSON: {e}"))?;
+    if let Some(bad) = report
+        .insights
+        .iter()
+        .find(|i| !matches!(i.kind.as_str(), "critical" | "warning" | "suggestion"))
+    {
+        return Err(format!("Unknown insight type '{}'", bad.kind));
+    }
+    Ok(report)
+}
+
+// ── Stream lines ──────────────────────────────────────────────────────────────
+
+/// Splits a streamed response body into lines. Network chunks can end
+/// mid-line or even mid-character, so bytes are held until a `\n` arrives and
+/// only whole lines are decoded.
+#[derive(Default)]
+struct LineBuffer {
+    bytes: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Appends a chunk and returns the lines it completed.
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.bytes.extend_from_slice(chunk);
+        let Some(last_newline) = self.bytes.iter().rposition(|&b| b == b'\n') else {
+            return Vec::new();
+        };
+        let rest = self.bytes.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.bytes, rest);
+        String::from_utf8_lossy(&complete).lines().map(str::to_string).collect()
+    }
+
+    /// Returns whatever is left once the stream ends without a final newline.
+    fn finish(&mut self) -> Vec<String> {
+        let rest = std::mem::take(&mut self.bytes);
+        String::from_utf8_lossy(&rest).lines().map(str::to_string).collect()
     }
 }
 
@@ -113,6 +292,49 @@ impl AiClient {
         self.chat_with_events(messages, app, "ai-stream", "ai-done").await
     }
 
+    /// Asks for `prompt`'s insights through the provider's structured output —
+    /// a forced `tool_use` on Anthropic, `response_format: json_schema` on
+    /// OpenAI / Azure — so the reply always has the `InsightReport` shape.
+    /// Not streamed. Ok(None) means the provider or endpoint doesn't support
+    /// it (Ollama, or a 400/404/422) and the caller should use `chat`.
+    pub async fn insights(&self, prompt: String) -> Result<Option<InsightReport>, String> {
+        let messages = vec![serde_json::json!({ "role": "user", "content": prompt })];
+        match self.config.provider {
+            AiProvider::Anthropic => self.insights_anthropic(messages).await,
+            AiProvider::OpenAI | AiProvider::Azure => self.insights_openai_compat(messages).await,
+            AiProvider::Ollama => Ok(None),
+        }
+    }
+
+    /// Runs an analysis prompt that asks for `{ "insights": [...] }`. Uses
+    /// structured output when the provider has it (see `insights`), else
+    /// streams like `chat` and parses the reply with `extract_insights`.
+    ///
+    /// Events emitted:
+    ///   "ai-insight"     → AiInsight, one per insight (structured output only)
+    ///   "ai-stream"      → String token (prose fallback only)
+    ///   "ai-done"        → String, the report JSON or the full prose
+    ///   "ai-insights"    → InsightReport, once parsed
+    ///   "ai-parse-error" → AiParseError when the prose isn't a valid report
+    pub async fn analyze(&self, prompt: String, app: &tauri::AppHandle) -> Result<(), String> {
+        if let Some(report) = self.insights(prompt.clone()).await? {
+            for insight in &report.insights {
+                app.emit("ai-insight", insight).map_err(|e| e.to_string())?;
+            }
+            let done = serde_json::to_string(&report).map_err(|e| e.to_string())?;
+            app.emit("ai-done", done).map_err(|e| e.to_string())?;
+            return app.emit("ai-insights", &report).map_err(|e| e.to_string());
+        }
+
+        let messages = vec![serde_json::json!({ "role": "user", "content": prompt })];
+        let raw = self.stream(messages, app, "ai-stream", "ai-done").await?;
+        match extract_insights(&raw) {
+            Ok(report) => app.emit("ai-insights", report),
+            Err(error) => app.emit("ai-parse-error", AiParseError { error, raw }),
+        }
+        .map_err(|e| e.to_string())
+    }
+
     /// Like `chat` but accepts a full messages array (for multi-turn conversations)
     /// and custom event names for the stream/done events.
     pub async fn chat_with_events(
@@ -122,6 +344,18 @@ impl AiClient {
         stream_event: &str,
         done_event: &str,
     ) -> Result<(), String> {
+        self.stream(messages, app, stream_event, done_event).await.map(drop)
+    }
+
+    /// Streams the reply through `stream_event` / `done_event` and returns the
+    /// accumulated text.
+    async fn stream(
+        &self,
+        messages: Vec<serde_json::Value>,
+        app: &tauri::AppHandle,
+        stream_event: &str,
+        done_event: &str,
+    ) -> Result<String, String> {
         match self.config.provider {
             AiProvider::Anthropic => {
                 self.chat_anthropic(messages, app, stream_event, done_event).await
@@ -138,49 +372,108 @@ impl AiClient {
     // ── Anthropic ─────────────────────────────────────────────────────────────
     // SSE stream; delta token at data.delta.text
 
+    fn anthropic_request(
+        &self,
+        body: &serde_json::Value,
+    ) -> Result<reqwest::RequestBuilder, String> {
+        let api_key = self.config.api_key.as_deref()
+            .ok_or("API key not set")?;
+
+        Ok(self.client
+            .post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(body))
+    }
+
+    // Forced tool call; the report is the tool_use block's `input`.
+    async fn insights_anthropic(
+        &self,
+        messages: Vec<serde_json::Value>,
+    ) -> Result<Option<InsightReport>, String> {
+        let mut body = serde_json::json!({
+            "model":       self.config.model,
+            "max_tokens":  self.config.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            "messages":    messages,
+            "tools": [{
+                "name":         INSIGHTS_SCHEMA_NAME,
+                "description":  "Report the findings of the analysis.",
+                "input_schema": insights_schema(),
+            }],
+            "tool_choice": { "type": "tool", "name": INSIGHTS_SCHEMA_NAME },
+        });
+        if let Some(t) = self.config.temperature {
+            body["temperature"] = serde_json::json!(t);
+        }
+
+        let response = self.anthropic_request(&body)?
+            .send()
+            .await
+            .map_err(|e| format!("Anthropic request failed: {e}"))?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        if structured_unsupported(status, &body) {
+            return Ok(None);
+        }
+        let json: serde_json::Value = serde_json::from_str(&body).map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            let message = &json["error"]["message"];
+            return Err(format!("Anthropic request failed ({status}): {message}"));
+        }
+
+        let input = json["content"]
+            .as_array()
+            .and_then(|blocks| blocks.iter().find(|b| b["type"] == "tool_use"))
+            .map(|b| b["input"].clone())
+            .ok_or("Anthropic response has no tool_use block")?;
+        serde_json::from_value(input)
+            .map(Some)
+            .map_err(|e| format!("Malformed insights from Anthropic: {e}"))
+    }
+
     async fn chat_anthropic(
         &self,
         messages: Vec<serde_json::Value>,
         app: &tauri::AppHandle,
         stream_event: &str,
         done_event: &str,
-    ) -> Result<(), String> {
-        let api_key = self.config.api_key.as_deref()
-            .ok_or("API key not set")?;
-
-        let body = serde_json::json!({
+    ) -> Result<String, String> {
+        let mut body = serde_json::json!({
             "model":      self.config.model,
-            "max_tokens": 4096,
+            "max_tokens": self.config.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
             "stream":     true,
             "messages":   messages,
         });
+        if let Some(t) = self.config.temperature {
+            body["temperature"] = serde_json::json!(t);
+        }
 
-        let mut response = self.client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&body)
+        let mut response = self.anthropic_request(&body)?
             .send()
             .await
             .map_err(|e| format!("Anthropic request failed: {e}"))?;
 
         let mut buffer = String::new();
 
-        'outer: loop {
-            match response.chunk().await.map_err(|e| e.to_string())? {
-                None => break,
-                Some(chunk) => {
-                    let text = String::from_utf8_lossy(&chunk);
-                    for line in text.lines() {
-                        if let Some(data) = line.strip_prefix("data: ") {
-                            if data.trim() == "[DONE]" { break 'outer; }
-                            if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
-                                if let Some(delta) = json["delta"]["text"].as_str() {
-                                    buffer.push_str(delta);
-                                    app.emit(stream_event, delta).map_err(|e| e.to_string())?;
-                                }
-                            }
+        let mut lines = LineBuffer::default();
+        let mut ended = false;
+
+        'outer: while !ended {
+            let batch = match response.chunk().await.map_err(|e| e.to_string())? {
+                Some(chunk) => lines.push(&chunk),
+                None => {
+                    ended = true;
+                    lines.finish()
+                }
+            };
+            for line in batch {
+                if let Some(data) = line.strip_prefix("data: ") {
+                    if data.trim() == "[DONE]" { break 'outer; }
+                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
+                        if let Some(delta) = json["delta"]["text"].as_str() {
+                            buffer.push_str(delta);
+                            app.emit(stream_event, delta).map_err(|e| e.to_string())?;
                         }
                     }
                 }
@@ -188,20 +481,17 @@ impl AiClient {
         }
 
         app.emit(done_event, &buffer).map_err(|e| e.to_string())?;
-        Ok(())
+        Ok(buffer)
     }
 
     // ── OpenAI / Azure ────────────────────────────────────────────────────────
     // SSE stream; delta token at data.choices[0].delta.content
     // Also handles Open WebUI and other non-standard response formats.
 
-    async fn chat_openai_compat(
+    fn openai_request(
         &self,
-        messages: Vec<serde_json::Value>,
-        app: &tauri::AppHandle,
-        stream_event: &str,
-        done_event: &str,
-    ) -> Result<(), String> {
+        body: &serde_json::Value,
+    ) -> Result<reqwest::RequestBuilder, String> {
         let api_key = self.config.api_key.as_deref()
             .ok_or("API key not set")?;
 
@@ -218,62 +508,126 @@ impl AiClient {
             "https://api.openai.com/v1/chat/completions".to_string()
         };
 
-        let body = serde_json::json!({
-            "model":    self.config.model,
-            "stream":   true,
-            "messages": messages,
-        });
-
         // Azure uses `api-key` header; OpenAI uses Bearer token.
         let request = self.client
             .post(&url)
             .header("content-type", "application/json")
-            .json(&body);
+            .json(body);
 
-        let request = if matches!(self.config.provider, AiProvider::Azure) {
+        Ok(if matches!(self.config.provider, AiProvider::Azure) {
             request.header("api-key", api_key)
         } else {
             request.header("Authorization", format!("Bearer {api_key}"))
-        };
+        })
+    }
 
-        let mut response = request
+    // Strict json_schema response format; the report is the message content.
+    async fn insights_openai_compat(
+        &self,
+        messages: Vec<serde_json::Value>,
+    ) -> Result<Option<InsightReport>, String> {
+        let mut body = serde_json::json!({
+            "model":      self.config.model,
+            "messages":   messages,
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": {
+                    "name":   INSIGHTS_SCHEMA_NAME,
+                    "strict": true,
+                    "schema": insights_schema(),
+                },
+            },
+        });
+        if let Some(n) = self.config.max_tokens {
+            body["max_tokens"] = serde_json::json!(n);
+        }
+        if let Some(t) = self.config.temperature {
+            body["temperature"] = serde_json::json!(t);
+        }
+
+        let response = self.openai_request(&body)?
+            .send()
+            .await
+            .map_err(|e| format!("OpenAI/Azure request failed: {e}"))?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        if structured_unsupported(status, &body) {
+            return Ok(None);
+        }
+        let json: serde_json::Value = serde_json::from_str(&body).map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            let message = &json["error"]["message"];
+            return Err(format!("OpenAI/Azure request failed ({status}): {message}"));
+        }
+
+        let message = &json["choices"][0]["message"];
+        if let Some(refusal) = message["refusal"].as_str() {
+            return Err(format!("Model refused the analysis: {refusal}"));
+        }
+        let content = message["content"].as_str()
+            .ok_or("OpenAI/Azure response has no message content")?;
+        serde_json::from_str(content)
+            .map(Some)
+            .map_err(|e| format!("Malformed insights from OpenAI/Azure: {e}"))
+    }
+
+    async fn chat_openai_compat(
+        &self,
+        messages: Vec<serde_json::Value>,
+        app: &tauri::AppHandle,
+        stream_event: &str,
+        done_event: &str,
+    ) -> Result<String, String> {
+        let mut body = serde_json::json!({
+            "model":      self.config.model,
+            "stream":     true,
+            "messages":   messages,
+        });
+        if let Some(n) = self.config.max_tokens {
+            body["max_tokens"] = serde_json::json!(n);
+        }
+        if let Some(t) = self.config.temperature {
+            body["temperature"] = serde_json::json!(t);
+        }
+
+        let mut response = self.openai_request(&body)?
             .send()
             .await
             .map_err(|e| format!("OpenAI/Azure request failed: {e}"))?;
 
         let mut buffer = String::new();
 
-        'outer: loop {
-            match response.chunk().await.map_err(|e| e.to_string())? {
-                None => break,
-                Some(chunk) => {
-                    let text = String::from_utf8_lossy(&chunk);
-                    for line in text.lines() {
-                        if let Some(data) = line.strip_prefix("data: ") {
-                            if data.trim() == "[DONE]" { break 'outer; }
-
-                            match serde_json::from_str::<serde_json::Value>(data) {
-                                Ok(json) => {
-
-                                    // Try multiple content locations for provider compatibility:
-                                    // 1. choices[0].delta.content   — standard OpenAI streaming
-                                    // 2. choices[0].message.content — non-streaming / Open WebUI
-                                    // 3. message.content            — Ollama-compatible format
-                                    // 4. content                    — direct content field
-                                    let delta = json["choices"][0]["delta"]["content"].as_str()
-                                        .or_else(|| json["choices"][0]["message"]["content"].as_str())
-                                        .or_else(|| json["message"]["content"].as_str())
-                                        .or_else(|| json["content"].as_str());
-
-                                    if let Some(delta) = delta {
-                                        if !delta.is_empty() {
-                                            buffer.push_str(delta);
-                                            app.emit(stream_event, delta)
-                                                .map_err(|e| e.to_string())?;
-                                        }
-                                    }
-                                }
-                                Err(_) => {}
+        let mut lines = LineBuffer::default();
+        let mut ended = false;
+
+        'outer: while !ended {
+            let batch = match response.chunk().await.map_err(|e| e.to_string())? {
+                Some(chunk) => lines.push(&chunk),
+                None => {
+                    ended = true;
+                    lines.finish()
+                }
+            };
+            for line in batch {
+                if let Some(data) = line.strip_prefix("data: ") {
+                    if data.trim() == "[DONE]" { break 'outer; }
+
+                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
+                        // Try multiple content locations for provider compatibility:
+                        // 1. choices[0].delta.content   — standard OpenAI streaming
+                        // 2. choices[0].message.content — non-streaming / Open WebUI
+                        // 3. message.content            — Ollama-compatible format
+                        // 4. content                    — direct content field
+                        let delta = json["choices"][0]["delta"]["content"].as_str()
+                            .or_else(|| json["choices"][0]["message"]["content"].as_str())
+                            .or_else(|| json["message"]["content"].as_str())
+                            .or_else(|| json["content"].as_str());
+
+                        if let Some(delta) = delta {
+                            if !delta.is_empty() {
+                                buffer.push_str(delta);
+                                app.emit(stream_event, delta)
+                                    .map_err(|e| e.to_string())?;
                             }
                         }
                     }
@@ -287,7 +641,7 @@ impl AiClient {
         } else {
             app.emit(done_event, &buffer).map_err(|e| e.to_string())?;
         }
-        Ok(())
+        Ok(buffer)
     }
 
     // ── Ollama ────────────────────────────────────────────────────────────────
@@ -299,15 +653,25 @@ impl AiClient {
         app: &tauri::AppHandle,
         stream_event: &str,
         done_event: &str,
-    ) -> Result<(), String> {
+    ) -> Result<String, String> {
         let base = self.config.base_url.as_deref()
             .unwrap_or("http://localhost:11434");
         let url = format!("{base}/api/chat");
 
+        // Ollama takes sampling settings under `options`; num_predict caps output tokens.
+        let mut options = serde_json::json!({});
+        if let Some(n) = self.config.max_tokens {
+            options["num_predict"] = serde_json::json!(n);
+        }
+        if let Some(t) = self.config.temperature {
+            options["temperature"] = serde_json::json!(t);
+        }
+
         let body = serde_json::json!({
             "model":    self.config.model,
             "stream":   true,
             "messages": messages,
+            "options":  options,
         });
 
         let mut response = self.client
@@ -320,31 +684,121 @@ impl AiClient {
 
         let mut buffer = String::new();
 
-        'outer: loop {
-            match response.chunk().await.map_err(|e| e.to_string())? {
-                None => break,
-                Some(chunk) => {
-                    let text = String::from_utf8_lossy(&chunk);
-                    for line in text.lines() {
-                        let line = line.trim();
-                        if line.is_empty() { continue; }
-                        if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
-                            if let Some(content) = json["message"]["content"].as_str() {
-                                if !content.is_empty() {
-                                    buffer.push_str(content);
-                                    app.emit(stream_event, content).map_err(|e| e.to_string())?;
-                                }
-                            }
-                            if json["done"].as_bool().unwrap_or(false) {
-                                break 'outer;
-                            }
+        let mut lines = LineBuffer::default();
+        let mut ended = false;
+
+        'outer: while !ended {
+            let batch = match response.chunk().await.map_err(|e| e.to_string())? {
+                Some(chunk) => lines.push(&chunk),
+                None => {
+                    ended = true;
+                    lines.finish()
+                }
+            };
+            for line in batch {
+                let line = line.trim();
+                if line.is_empty() { continue; }
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
+                    if let Some(content) = json["message"]["content"].as_str() {
+                        if !content.is_empty() {
+                            buffer.push_str(content);
+                            app.emit(stream_event, content).map_err(|e| e.to_string())?;
                         }
                     }
+                    if json["done"].as_bool().unwrap_or(false) {
+                        break 'outer;
+                    }
                 }
             }
         }
 
         app.emit(done_event, &buffer).map_err(|e| e.to_string())?;
-        Ok(())
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_only_when_the_error_names_structured_output() {
+        let bad_request = reqwest::StatusCode::BAD_REQUEST;
+        let unsupported = r#"{"error":{"message":"Unrecognized argument: response_format"}}"#;
+        assert!(structured_unsupported(bad_request, unsupported));
+        assert!(structured_unsupported(
+            reqwest::StatusCode::UNPROCESSABLE_ENTITY,
+            r#"{"detail":"json_schema is not supported"}"#,
+        ));
+
+        let bad_model = r#"{"error":{"message":"The model `gpt-9` does not exist"}}"#;
+        assert!(!structured_unsupported(bad_request, bad_model));
+        assert!(!structured_unsupported(bad_request, "prompt is too long: 250000 tokens"));
+        assert!(!structured_unsupported(reqwest::StatusCode::UNAUTHORIZED, unsupported));
+    }
+
+    #[test]
+    fn extracts_insights_from_a_fenced_reply() {
+        let raw = "```json\n{\"insights\": [{\"type\": \"warning\", \"title\": \"t\", \
+                   \"body\": \"b\", \"command\": null}]}\n```";
+        let report = extract_insights(raw).unwrap();
+        assert_eq!(report.insights.len(), 1);
+        assert_eq!(report.insights[0].kind, "warning");
+    }
+
+    #[test]
+    fn extracts_insights_after_prose() {
+        let raw = "Here is what I found:\n{\"insights\": [{\"type\": \"critical\", \
+                   \"title\": \"OOM\", \"body\": \"raise the limit\"}]}\nHope that helps.";
+        let report = extract_insights(raw).unwrap();
+        assert_eq!(report.insights[0].title, "OOM");
+        assert_eq!(report.insights[0].command, None);
+    }
+
+    #[test]
+    fn tolerates_trailing_commas() {
+        let raw = r#"{"insights": [{"type": "suggestion", "title": "t", "body": "b",},],}"#;
+        assert_eq!(extract_insights(raw).unwrap().insights.len(), 1);
+    }
+
+    #[test]
+    fn keeps_commas_inside_strings() {
+        assert_eq!(
+            strip_trailing_commas(r#"{"a": "x,}", "b": [1, 2,]}"#),
+            r#"{"a": "x,}", "b": [1, 2]}"#
+        );
+        assert_eq!(strip_trailing_commas(r#"{"a": "q\",]"}"#), r#"{"a": "q\",]"}"#);
+    }
+
+    #[test]
+    fn line_buffer_joins_a_character_split_across_chunks() {
+        let mut buf = LineBuffer::default();
+        let text = "data: héllo\n".as_bytes();
+        let split = text.iter().position(|&b| b == 0xC3).unwrap() + 1;
+        assert!(buf.push(&text[..split]).is_empty());
+        assert_eq!(buf.push(&text[split..]), ["data: héllo"]);
+    }
+
+    #[test]
+    fn line_buffer_holds_a_partial_line_until_finish() {
+        let mut buf = LineBuffer::default();
+        assert_eq!(buf.push(b"data: one\ndata: tw"), ["data: one"]);
+        assert_eq!(buf.push(b"o\ndata: thr"), ["data: two"]);
+        assert_eq!(buf.finish(), ["data: thr"]);
+        assert!(buf.finish().is_empty());
+    }
+
+    #[test]
+    fn line_buffer_strips_crlf() {
+        let mut buf = LineBuffer::default();
+        assert_eq!(buf.push(b"data: a\r\n\r\ndata: b\r"), ["data: a", ""]);
+        assert_eq!(buf.push(b"\n"), ["data: b"]);
+    }
+
+    #[test]
+    fn rejects_unknown_insight_types() {
+        let raw = r#"{"insights": [{"type": "info", "title": "t", "body": "b"}]}"#;
+        assert!(extract_insights(raw).unwrap_err().contains("info"));
+        assert!(extract_insights("no json here").is_err());
     }
 }
@@ -1,8 +1,38 @@
 // AI analysis commands — all providers handled via AiClient in ai_provider.rs
 
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
 use crate::ai_provider::{AiClient, AiConfig};
 
+// ── input sizing ──────────────────────────────────────────────────────────────
+
+/// Largest kubectl output (in characters) sent for analysis — roughly 25k
+/// tokens, leaving room for the prompt and response in common context windows.
+const MAX_INPUT_CHARS: usize = 100_000;
+
+/// Shrinks `output` to `MAX_INPUT_CHARS`, keeping the end for logs (most
+/// recent lines matter) and the start otherwise. Emits `ai-input-truncated`
+/// with `{ originalChars, keptChars }` when anything is dropped.
+fn fit_input(app: &AppHandle, output: String, keep_tail: bool) -> Result<String, String> {
+    let total = output.chars().count();
+    if total <= MAX_INPUT_CHARS {
+        return Ok(output);
+    }
+
+    let kept: String = if keep_tail {
+        output.chars().skip(total - MAX_INPUT_CHARS).collect()
+    } else {
+        output.chars().take(MAX_INPUT_CHARS).collect()
+    };
+
+    app.emit(
+        "ai-input-truncated",
+        serde_json::json!({ "originalChars": total, "keptChars": MAX_INPUT_CHARS }),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(kept)
+}
+
 // ── get_ai_provider_name ──────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -66,13 +96,40 @@ pub async fn ask_ai(
         .await
 }
 
+/// Analyzes describe, log, or arbitrary kubectl command output. `max_tokens` / `temperature` override the
+/// provider defaults; oversized `output` is truncated (see `fit_input`).
+/// Results arrive as `AiClient::analyze` events.
 #[tauri::command]
 pub async fn analyze_with_ai(
     app: AppHandle,
     output: String,
-    mode: String, // "describe" or "logs"
+    mode: String, // "describe", "logs" or "command"
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
 ) -> Result<(), String> {
-    let prompt = if mode == "logs" {
+    let output = fit_input(&app, output, mode == "logs")?;
+
+    let prompt = if mode == "command" {
+        format!(
+            "You are a Kubernetes operations expert. The user ran a kubectl command \
+             and wants to understand its output. Analyze it and:\n\
+             1. Explain what the output shows in plain terms\n\
+             2. Point out anything unhealthy, misconfigured, or unusual\n\
+             3. Suggest specific next kubectl commands to investigate or fix issues\n\n\
+             Respond ONLY with a JSON object:\n\
+             {{\n\
+               \"insights\": [\n\
+                 {{\n\
+                   \"type\": \"critical\" | \"warning\" | \"suggestion\",\n\
+                   \"title\": \"Short title\",\n\
+                   \"body\": \"Explanation\",\n\
+                   \"command\": \"kubectl command if applicable (optional)\"\n\
+                 }}\n\
+               ]\n\
+             }}\n\n\
+             kubectl command output:\n{output}"
+        )
+    } else if mode == "logs" {
         format!(
             "You are a Kubernetes operations expert. Analyze these pod logs and identify:\n\
              1. Any errors, crashes, panics, or fatal issues\n\
@@ -113,8 +170,10 @@ pub async fn analyze_with_ai(
         )
     };
 
-    let config = AiConfig::from_env()?;
-    AiClient::new(config).chat(prompt, &app).await
+    let mut config = AiConfig::from_env()?;
+    config.max_tokens = max_tokens.or(config.max_tokens);
+    config.temperature = temperature.or(config.temperature);
+    AiClient::new(config).analyze(prompt, &app).await
 }
 
 // ── analyze_security ──────────────────────────────────────────────────────────
@@ -167,7 +226,7 @@ kubectl describe pod output:\n{output}"
     );
 
     let config = AiConfig::from_env()?;
-    AiClient::new(config).chat(prompt, &app).await
+    AiClient::new(config).analyze(prompt, &app).await
 }
 
 // ── analyze_network_scan ──────────────────────────────────────────────────────
@@ -210,7 +269,7 @@ Network configuration data:\n{output}"
     );
 
     let config = AiConfig::from_env()?;
-    AiClient::new(config).chat(prompt, &app).await
+    AiClient::new(config).analyze(prompt, &app).await
 }
 
 // ── analyze_rbac_scan ─────────────────────────────────────────────────────────
@@ -255,7 +314,7 @@ RBAC configuration data:\n{output}"
     );
 
     let config = AiConfig::from_env()?;
-    AiClient::new(config).chat(prompt, &app).await
+    AiClient::new(config).analyze(prompt, &app).await
 }
 
 // ── analyze_namespace_scan ────────────────────────────────────────────────────
@@ -300,7 +359,7 @@ Namespace configuration data:\n{output}"
     );
 
     let config = AiConfig::from_env()?;
-    AiClient::new(config).chat(prompt, &app).await
+    AiClient::new(config).analyze(prompt, &app).await
 }
 
 // ── analyze_node_scan ─────────────────────────────────────────────────────────
@@ -347,5 +406,5 @@ Node data:\n{output}"
     );
 
     let config = AiConfig::from_env()?;
-    AiClient::new(config).chat(prompt, &app).await
+    AiClient::new(config).analyze(prompt, &app).await
 }
@@ -0,0 +1,111 @@
+use tauri::{AppHandle, Manager, State};
+use tokio::process::Command;
+
+use crate::commands::kubeconfig::{
+    is_wsl, list_kubeconfig_files, scan_kubeconfig_contexts, search_paths,
+};
+use crate::commands::kubectl::resolve_kubectl;
+use crate::commands::pods::in_cluster_env;
+use crate::commands::proxy::recent_proxy_output;
+use crate::models::k8s::{ContextServer, SupportBundle};
+use crate::{AppSettings, KubectlProxy, ProxyHealth, ProxyLogs};
+
+/// Proxy output lines included in a support bundle.
+const BUNDLE_PROXY_LINES: usize = 100;
+
+// ── collect_diagnostics ───────────────────────────────────────────────────────
+
+/// `kubectl version --client` as reported by the binary, e.g. "v1.30.2".
+async fn kubectl_client_version(kubectl: &str) -> Option<String> {
+    let output = Command::new(kubectl)
+        .args(["version", "--client", "-o", "json"])
+        .output()
+        .await
+        .ok()?;
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    json["clientVersion"]["gitVersion"].as_str().map(str::to_string)
+}
+
+/// Host part of `url`, when it parses as a URL with one.
+fn url_host(url: &str) -> Option<String> {
+    reqwest::Url::parse(url).ok()?.host_str().map(str::to_string)
+}
+
+/// Gathers what a bug report needs in one call: app version, platform
+/// (WSL / native / in-cluster), the resolved kubectl and its version, every
+/// kubeconfig file with its parse status, each context's server and the
+/// proxy's state and recent output.
+///
+/// With `redact_hosts`, API server hostnames are replaced by "<redacted>" in
+/// server URLs and in the proxy output, so the bundle can be pasted publicly.
+#[tauri::command]
+pub async fn collect_diagnostics(
+    app: AppHandle,
+    redact_hosts: Option<bool>,
+    proxy: State<'_, KubectlProxy>,
+    logs: State<'_, ProxyLogs>,
+    health: State<'_, ProxyHealth>,
+) -> Result<SupportBundle, String> {
+    let kubectl_path = resolve_kubectl().map(|p| p.to_string_lossy().to_string());
+    let kubectl_version = match &kubectl_path {
+        Some(path) => kubectl_client_version(path).await,
+        None => None,
+    };
+
+    let settings = app.state::<AppSettings>();
+    let dirs = search_paths(&settings)?;
+    let kubeconfig_files = list_kubeconfig_files(settings).await?;
+    let mut contexts: Vec<ContextServer> = scan_kubeconfig_contexts(&dirs, true, None, None, false)?
+        .into_iter()
+        .map(|ctx| ContextServer {
+            context_name: ctx.context_name,
+            source_file: ctx.source_file,
+            server_url: ctx.server_url,
+        })
+        .collect();
+
+    let proxy_running = proxy
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_mut()
+        .is_some_and(|child| matches!(child.try_wait(), Ok(None)));
+    let proxy_last_healthy = health.0.lock().map_err(|e| e.to_string())?.map(|t| t.to_rfc3339());
+    let mut proxy_output: Vec<String> = recent_proxy_output(&logs.0, BUNDLE_PROXY_LINES)
+        .lines()
+        .map(str::to_string)
+        .collect();
+
+    if redact_hosts.unwrap_or(false) {
+        let hosts: Vec<String> = contexts
+            .iter()
+            .filter_map(|c| c.server_url.as_deref().and_then(url_host))
+            .collect();
+        let redact = |text: &str| {
+            hosts
+                .iter()
+                .fold(text.to_string(), |acc, host| acc.replace(host.as_str(), "<redacted>"))
+        };
+        for ctx in &mut contexts {
+            ctx.server_url = ctx.server_url.as_deref().map(redact);
+        }
+        for line in &mut proxy_output {
+            *line = redact(line);
+        }
+    }
+
+    Ok(SupportBundle {
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        wsl: is_wsl(),
+        in_cluster: in_cluster_env(),
+        kubectl_path,
+        kubectl_version,
+        kubeconfig_files,
+        contexts,
+        proxy_running,
+        proxy_last_healthy,
+        proxy_output,
+    })
+}
@@ -1,10 +1,26 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
-use kube::config::Kubeconfig;
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, ListParams};
+use kube::config::{AuthInfo, Kubeconfig};
+use tauri::{AppHandle, Emitter, Manager, State};
 
-use crate::models::k8s::KubeContext;
+use crate::commands::pods::build_context_client;
+use crate::commands::settings::{ensure_writable, request_limiter};
+use crate::models::k8s::{
+    AuthRequirements, ClusterCard, ConnectionDetails, ContextReachability, KubeContext,
+    KubeconfigFile, KubeconfigScanProgress, KubeconfigTest, NamespaceDiff,
+    OrphanedKubeconfigEntries, ReplicaDiff, SessionInvalidated, WorkloadRef,
+};
+use crate::settings::HealthThresholds;
+use crate::{
+    AppSettings, KubeconfigScanCancel, LogStreams, NamespaceWatch, PodLogStreams, PtyKiller,
+    PtyState, RequestLimiter, RolloutWatch, SwitchedContext,
+};
 
 // ── path helpers ──────────────────────────────────────────────────────────────
 
@@ -31,66 +47,300 @@ fn suffix_from_filename(filename: &str) -> Option<&str> {
         .filter(|s| !s.is_empty())
 }
 
-// ── commands ──────────────────────────────────────────────────────────────────
+/// Returns the user entry bound to `context_name`, if the kubeconfig has one.
+pub(crate) fn context_auth_info<'a>(
+    kubeconfig: &'a Kubeconfig,
+    context_name: &str,
+) -> Result<Option<&'a AuthInfo>, String> {
+    let user = kubeconfig
+        .contexts
+        .iter()
+        .find(|c| c.name == context_name)
+        .and_then(|c| c.context.as_ref())
+        .ok_or_else(|| format!("Context '{context_name}' not found in kubeconfig"))?
+        .user
+        .clone()
+        .unwrap_or_default();
 
-/// Lists all contexts found by scanning `~/.kube`:
-///
-/// 1. `~/.kube/config` (merged config) — contexts are used as-is; display
-///    name is the context name unless it is "local" or "default", in which
-///    case it falls back to the filename suffix (always "config" → no suffix,
-///    so it stays as the context name).
-///
-/// 2. `~/.kube/config.*` files — if context name is "local", display name is
-///    derived from the filename suffix; otherwise the context name is used.
-///
-/// Contexts are deduplicated by context name across all sources — the first
-/// occurrence wins (merged config is processed first).
-#[tauri::command]
-pub async fn get_kubeconfig_contexts() -> Result<Vec<KubeContext>, String> {
-    let kube_dir = dirs::home_dir()
-        .map(|h| h.join(".kube"))
-        .ok_or_else(|| "Cannot determine home directory".to_string())?;
+    Ok(kubeconfig
+        .auth_infos
+        .iter()
+        .find(|a| a.name == user)
+        .and_then(|a| a.auth_info.as_ref()))
+}
 
-    // ── (a) merged config ─────────────────────────────────────────────────────
-    // Parsed first so its contexts win deduplication.
-    let merged_path = kube_dir.join("config");
-    let mut parsed: Vec<(PathBuf, bool)> = Vec::new(); // (path, is_merged)
+/// Candidate kubeconfig files in `kube_dir` as `(path, is_merged)` pairs:
+/// the merged `config` first (so its contexts win deduplication), then the
+/// individual `config.*` files in name order. Backups (`*.bak`) are skipped.
+fn kubeconfig_candidates(kube_dir: &Path) -> Vec<(PathBuf, bool)> {
+    let mut candidates: Vec<(PathBuf, bool)> = Vec::new();
 
+    let merged_path = kube_dir.join("config");
     if merged_path.is_file() {
-        if Kubeconfig::read_from(&merged_path).is_ok() {
-            parsed.push((merged_path.clone(), true));
-        }
+        candidates.push((merged_path, true));
     }
 
-    // ── (b) individual config.* files ────────────────────────────────────────
     let mut individual_files: Vec<PathBuf> = Vec::new();
-    if let Ok(entries) = std::fs::read_dir(&kube_dir) {
+    if let Ok(entries) = std::fs::read_dir(kube_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
             if path.is_dir() {
                 continue;
             }
             let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-            if name.starts_with("config.") {
+            // Skip backups written by set_kubeconfig_raw.
+            if name.starts_with("config.") && !name.ends_with(".bak") {
                 individual_files.push(path);
             }
         }
     }
     individual_files.sort();
-    for path in individual_files {
-        parsed.push((path, false));
+    candidates.extend(individual_files.into_iter().map(|p| (p, false)));
+
+    candidates
+}
+
+/// Files listed in the KUBECONFIG env var that exist, as `(path, is_merged)`
+/// pairs in kubectl's order. A `config.<name>` entry is treated like an
+/// individual file (display name from the suffix); anything else as merged.
+fn kubeconfig_env_candidates() -> Vec<(PathBuf, bool)> {
+    let sep = if cfg!(windows) { ';' } else { ':' };
+
+    std::env::var("KUBECONFIG")
+        .unwrap_or_default()
+        .split(sep)
+        .map(|s| PathBuf::from(s.trim()))
+        .filter(|p| !p.as_os_str().is_empty() && p.is_file())
+        .map(|p| {
+            let filename = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            let is_merged = suffix_from_filename(filename).is_none();
+            (p, is_merged)
+        })
+        .collect()
+}
+
+/// Returns `~/.kube`, the directory scanned for kubeconfig files.
+fn kube_dir() -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .map(|h| h.join(".kube"))
+        .ok_or_else(|| "Cannot determine home directory".to_string())
+}
+
+/// Candidate files in an extra search directory: unlike `~/.kube`, any file
+/// name is accepted (e.g. `prod.yaml`), except hidden files and backups.
+/// Files that don't parse as a kubeconfig are dropped by the caller.
+fn search_path_candidates(dir: &Path) -> Vec<(PathBuf, bool)> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            !name.starts_with('.') && !name.ends_with(".bak")
+        })
+        .collect();
+    files.sort();
+    files.into_iter().map(|p| (p, false)).collect()
+}
+
+/// Candidate kubeconfig files from `~/.kube` followed by those from each
+/// configured search directory, in the order they were added. Directories
+/// that no longer exist are skipped; `cancel` is checked before each one.
+fn kube_dirs_to_scan(
+    search_paths: &[String],
+    cancel: Option<&AtomicBool>,
+) -> Result<Vec<(PathBuf, bool)>, String> {
+    let mut candidates = kubeconfig_candidates(&kube_dir()?);
+    for dir in search_paths.iter().map(PathBuf::from) {
+        if is_cancelled(cancel) {
+            return Err(SCAN_CANCELLED.to_string());
+        }
+        if dir.is_dir() {
+            candidates.extend(search_path_candidates(&dir));
+        }
     }
+    Ok(candidates)
+}
+
+/// Error returned by a scan stopped through `cancel_kubeconfig_scan`.
+const SCAN_CANCELLED: &str = "Kubeconfig scan cancelled";
 
+/// True once `cancel_kubeconfig_scan` has flagged the running scan.
+fn is_cancelled(cancel: Option<&AtomicBool>) -> bool {
+    cancel.is_some_and(|c| c.load(Ordering::Relaxed))
+}
+
+/// The `kubeconfig_search_paths` setting.
+pub(crate) fn search_paths(settings: &AppSettings) -> Result<Vec<String>, String> {
+    Ok(settings.0.lock().map_err(|e| e.to_string())?.kubeconfig_search_paths.clone())
+}
+
+// ── commands ──────────────────────────────────────────────────────────────────
+
+/// Lists all contexts found by scanning `~/.kube` and the configured search
+/// directories (see `add_kubeconfig_search_path`):
+///
+/// 1. `~/.kube/config` (merged config) — contexts are used as-is; display
+///    name is the context name unless it is "local" or "default", in which
+///    case it falls back to the filename suffix (always "config" → no suffix,
+///    so it stays as the context name).
+///
+/// 2. `~/.kube/config.*` files — if context name is "local", display name is
+///    derived from the filename suffix; otherwise the context name is used.
+///
+/// 3. every file in each search directory — handled like `config.*` files.
+///
+/// With `scan_all`, files listed in KUBECONFIG are scanned too, ahead of
+/// `~/.kube`, so contexts from an exported KUBECONFIG outside `~/.kube` show
+/// up alongside the directory's files. A file reachable both ways is read once.
+///
+/// Contexts are deduplicated by context name across all sources — the first
+/// occurrence wins (merged config is processed first). Each context keeps
+/// the file it came from in `source_file`.
+///
+/// Reading files can be slow (e.g. `/mnt/c` under WSL2), so the scan runs on
+/// a blocking thread, progress is reported per file and
+/// `cancel_kubeconfig_scan` stops it between directories and files.
+///
+/// Events emitted:
+///   "kubeconfig-scan-progress" → KubeconfigScanProgress after each file
+#[tauri::command]
+pub async fn get_kubeconfig_contexts(
+    app: AppHandle,
+    scan_all: Option<bool>,
+    cancel: State<'_, KubeconfigScanCancel>,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<KubeContext>, String> {
+    cancel.0.store(false, Ordering::Relaxed);
+    let dirs = search_paths(&settings)?;
+    let scan_all = scan_all.unwrap_or(false);
+    tokio::task::spawn_blocking(move || {
+        let cancel = app.state::<KubeconfigScanCancel>();
+        scan_kubeconfig_contexts(&dirs, scan_all, Some(&app), Some(&cancel.0), false)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Streaming variant of `get_kubeconfig_contexts` for startup: each file's
+/// contexts are emitted as soon as it is parsed, so the first ones render
+/// before slow files have been read.
+///
+/// A streamed context's `server_url` only reflects files parsed so far; the
+/// final list — returned, and also sent as "contexts-complete" — is
+/// authoritative and replaces them.
+///
+/// Events emitted:
+///   "context-found"            → KubeContext, per context as its file is parsed
+///   "kubeconfig-scan-progress" → KubeconfigScanProgress after each file
+///   "contexts-complete"        → Vec<KubeContext>, same as get_kubeconfig_contexts
+#[tauri::command]
+pub async fn stream_kubeconfig_contexts(
+    app: AppHandle,
+    scan_all: Option<bool>,
+    cancel: State<'_, KubeconfigScanCancel>,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<KubeContext>, String> {
+    cancel.0.store(false, Ordering::Relaxed);
+    let dirs = search_paths(&settings)?;
+    let scan_all = scan_all.unwrap_or(false);
+    let scan_app = app.clone();
+    let contexts = tokio::task::spawn_blocking(move || {
+        let cancel = scan_app.state::<KubeconfigScanCancel>();
+        scan_kubeconfig_contexts(&dirs, scan_all, Some(&scan_app), Some(&cancel.0), true)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    let _ = app.emit("contexts-complete", &contexts);
+    Ok(contexts)
+}
+
+/// Stops a running `get_kubeconfig_contexts` before its next directory or file; the
+/// command then returns an error instead of a partial list.
+#[tauri::command]
+pub async fn cancel_kubeconfig_scan(cancel: State<'_, KubeconfigScanCancel>) -> Result<(), String> {
+    cancel.0.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// The scan behind `get_kubeconfig_contexts`; `search_paths` is the
+/// `kubeconfig_search_paths` setting. Progress events need `app` and
+/// cancellation needs `cancel`; internal callers pass None for both. With
+/// `stream` (and `app`), "context-found" is emitted per context as each file
+/// is parsed.
+pub(crate) fn scan_kubeconfig_contexts(
+    search_paths: &[String],
+    scan_all: bool,
+    app: Option<&AppHandle>,
+    cancel: Option<&AtomicBool>,
+    stream: bool,
+) -> Result<Vec<KubeContext>, String> {
+    let mut parsed = Vec::new();
+    if scan_all {
+        parsed.extend(kubeconfig_env_candidates());
+    }
+    for (path, is_merged) in kube_dirs_to_scan(search_paths, cancel)? {
+        if is_cancelled(cancel) {
+            return Err(SCAN_CANCELLED.to_string());
+        }
+        let canonical = path.canonicalize().ok();
+        let duplicate = parsed
+            .iter()
+            .any(|(p, _): &(PathBuf, bool)| *p == path || p.canonicalize().ok() == canonical);
+        if !duplicate {
+            parsed.push((path, is_merged));
+        }
+    }
     if parsed.is_empty() {
         return Ok(vec![]);
     }
 
     // ── parse each file, keeping (path, Kubeconfig, is_merged) triples ───────
     let mut all: Vec<(PathBuf, Kubeconfig, bool)> = Vec::new();
-    for (path, is_merged) in &parsed {
+    // Streaming state: active pair, servers and names seen in files so far.
+    let mut streamed_active: Option<(String, String)> = None;
+    let mut streamed_servers: HashMap<String, String> = HashMap::new();
+    let mut streamed_seen: HashSet<String> = HashSet::new();
+    for (done, (path, is_merged)) in parsed.iter().enumerate() {
+        if is_cancelled(cancel) {
+            return Err(SCAN_CANCELLED.to_string());
+        }
         if let Ok(cfg) = Kubeconfig::read_from(path) {
+            if let (true, Some(app)) = (stream, app) {
+                if streamed_active.is_none() {
+                    streamed_active = cfg
+                        .current_context
+                        .clone()
+                        .map(|c| (path.to_string_lossy().into_owned(), c));
+                }
+                streamed_servers.extend(cluster_servers([&cfg]));
+                let (active_source, active_name) = streamed_active.clone().unwrap_or_default();
+                for ctx in file_contexts(
+                    path,
+                    &cfg,
+                    *is_merged,
+                    &streamed_servers,
+                    (&active_source, &active_name),
+                    &mut streamed_seen,
+                ) {
+                    let _ = app.emit("context-found", ctx);
+                }
+            }
             all.push((path.clone(), cfg, *is_merged));
         }
+        if let Some(app) = app {
+            let dir = path.parent().unwrap_or(path).to_string_lossy().into_owned();
+            let _ = app.emit(
+                "kubeconfig-scan-progress",
+                KubeconfigScanProgress {
+                    dir,
+                    files_done: done + 1,
+                    files_total: parsed.len(),
+                },
+            );
+        }
     }
 
     if all.is_empty() {
@@ -108,125 +358,1027 @@ pub async fn get_kubeconfig_contexts() -> Result<Vec<KubeContext>, String> {
         .unwrap_or_default();
 
     // ── build cluster → server URL map across all files ───────────────────────
-    let cluster_servers: HashMap<String, String> = all
-        .iter()
-        .flat_map(|(_, cfg, _)| cfg.clusters.iter())
+    let servers = cluster_servers(all.iter().map(|(_, cfg, _)| cfg));
+
+    // ── one KubeContext per context, deduplicating by context name ────────────
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut contexts: Vec<KubeContext> = Vec::new();
+    for (path, cfg, is_merged) in &all {
+        contexts.extend(file_contexts(
+            path,
+            cfg,
+            *is_merged,
+            &servers,
+            (&active_source, &active_ctx_name),
+            &mut seen,
+        ));
+    }
+
+    Ok(contexts)
+}
+
+/// Cluster name → server URL across `configs`; later files override earlier.
+fn cluster_servers<'a>(
+    configs: impl IntoIterator<Item = &'a Kubeconfig>,
+) -> HashMap<String, String> {
+    configs
+        .into_iter()
+        .flat_map(|cfg| cfg.clusters.iter())
         .filter_map(|nc| {
             let server = nc.cluster.as_ref()?.server.clone()?;
             Some((nc.name.clone(), server))
         })
-        .collect();
+        .collect()
+}
 
-    // ── emit one KubeContext per context, deduplicating by context name ───────
-    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
-    let mut contexts: Vec<KubeContext> = Vec::new();
+/// The contexts of one parsed file that are not already in `seen` — the
+/// first occurrence of a context name wins. `active` is the (source file,
+/// context name) pair that is current.
+fn file_contexts(
+    path: &Path,
+    cfg: &Kubeconfig,
+    is_merged: bool,
+    cluster_servers: &HashMap<String, String>,
+    active: (&str, &str),
+    seen: &mut HashSet<String>,
+) -> Vec<KubeContext> {
+    let source = path.to_string_lossy().into_owned();
+    let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let mut contexts = Vec::new();
 
-    for (path, cfg, is_merged) in &all {
-        let source = path.to_string_lossy().into_owned();
-        let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    for named in &cfg.contexts {
+        let Some(ctx) = named.context.as_ref() else {
+            continue;
+        };
+        let context_name = named.name.clone();
 
-        for named in &cfg.contexts {
-            let Some(ctx) = named.context.as_ref() else {
-                continue;
-            };
-            let context_name = named.name.clone();
+        // First occurrence of a context name wins.
+        if !seen.insert(context_name.clone()) {
+            continue;
+        }
 
-            // First occurrence of a context name wins.
-            if !seen.insert(context_name.clone()) {
-                continue;
+        let display_name = if is_merged {
+            // Merged config: context names are already descriptive
+            // (e.g. "eagle-i-orc", "rovi"). Use directly.
+            context_name.clone()
+        } else {
+            // Individual config.* file: if context name is "local",
+            // derive display name from filename suffix.
+            if context_name == "local" {
+                suffix_from_filename(filename)
+                    .unwrap_or(&context_name)
+                    .to_string()
+            } else {
+                context_name.clone()
             }
+        };
 
-            let display_name = if *is_merged {
-                // Merged config: context names are already descriptive
-                // (e.g. "eagle-i-orc", "rovi"). Use directly.
-                context_name.clone()
-            } else {
-                // Individual config.* file: if context name is "local",
-                // derive display name from filename suffix.
-                if context_name == "local" {
-                    suffix_from_filename(filename)
-                        .unwrap_or(&context_name)
-                        .to_string()
-                } else {
-                    context_name.clone()
+        let server_url = cluster_servers.get(&ctx.cluster).cloned();
+        let is_active = source == active.0 && context_name == active.1;
+
+        contexts.push(KubeContext {
+            display_name,
+            context_name,
+            source_file: source.clone(),
+            cluster: ctx.cluster.clone(),
+            user: ctx.user.clone().unwrap_or_default(),
+            is_active,
+            server_url,
+        });
+    }
+
+    contexts
+}
+
+/// True when running inside WSL, where Linux openers usually aren't set up.
+pub(crate) fn is_wsl() -> bool {
+    std::env::var_os("WSL_DISTRO_NAME").is_some()
+        || std::fs::read_to_string("/proc/version")
+            .is_ok_and(|v| v.to_lowercase().contains("microsoft"))
+}
+
+/// Spawns `cmd` without waiting for it; a background thread reaps the child
+/// when it exits so it doesn't linger as a zombie.
+fn spawn_detached(cmd: &mut std::process::Command) -> std::io::Result<()> {
+    let mut child = cmd.spawn()?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// Opens a kubeconfig file for manual editing: `$VISUAL` when set (may
+/// include arguments, e.g. `code -w`), else the OS default handler. `$EDITOR`
+/// is ignored: it usually names a terminal editor (vim, nano), which can't
+/// run without the TTY a GUI app doesn't have.
+/// Under WSL the path is translated with `wslpath -w` and handed to the
+/// Windows shell, so `/mnt/c/...` and `\\wsl$\...` files both open.
+#[tauri::command]
+pub async fn open_kubeconfig_in_editor(source_file: String) -> Result<(), String> {
+    let path = PathBuf::from(&source_file);
+    if !path.is_file() {
+        return Err(format!("File not found: {source_file}"));
+    }
+
+    let editor = std::env::var("VISUAL").ok().filter(|v| !v.trim().is_empty());
+
+    if let Some(editor) = editor {
+        let mut parts = editor.split_whitespace();
+        let program = parts.next().unwrap_or_default();
+        return spawn_detached(std::process::Command::new(program).args(parts).arg(&path))
+            .map_err(|e| format!("Failed to launch editor '{program}': {e}"));
+    }
+
+    if !cfg!(windows) && is_wsl() {
+        let output = std::process::Command::new("wslpath")
+            .arg("-w")
+            .arg(&path)
+            .output()
+            .map_err(|e| format!("wslpath failed: {e}"))?;
+        let windows_path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() && !windows_path.is_empty() {
+            return spawn_detached(std::process::Command::new("explorer.exe").arg(windows_path))
+                .map_err(|e| format!("Failed to open with Windows handler: {e}"));
+        }
+    }
+
+    opener::open(&path).map_err(|e| format!("Failed to open {source_file}: {e}"))
+}
+
+/// Lists every kubeconfig file discovered in `~/.kube` and the search
+/// directories with its contexts, including files that fail to parse (with
+/// the parse error), for the kubeconfig files management view.
+#[tauri::command]
+pub async fn list_kubeconfig_files(
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<KubeconfigFile>, String> {
+    Ok(kube_dirs_to_scan(&search_paths(&settings)?, None)?
+        .into_iter()
+        .map(|(path, _)| {
+            let path_str = path.to_string_lossy().into_owned();
+            match Kubeconfig::read_from(&path) {
+                Ok(cfg) => {
+                    let contexts: Vec<String> =
+                        cfg.contexts.into_iter().map(|c| c.name).collect();
+                    KubeconfigFile {
+                        path: path_str,
+                        context_count: contexts.len(),
+                        contexts,
+                        is_valid: true,
+                        parse_error: None,
+                    }
                 }
-            };
+                Err(e) => KubeconfigFile {
+                    path: path_str,
+                    context_count: 0,
+                    contexts: vec![],
+                    is_valid: false,
+                    parse_error: Some(e.to_string()),
+                },
+            }
+        })
+        .collect())
+}
 
-            let server_url = cluster_servers.get(&ctx.cluster).cloned();
-            let is_active = source == active_source && context_name == active_ctx_name;
+/// Adds a directory to scan for kubeconfig files (see `get_kubeconfig_contexts`)
+/// and returns the updated list. The path must be an existing directory; it
+/// is stored canonicalized, and adding one twice is a no-op.
+#[tauri::command]
+pub async fn add_kubeconfig_search_path(
+    app: AppHandle,
+    path: String,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<String>, String> {
+    let dir = PathBuf::from(path.trim());
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    let dir = dir.canonicalize().unwrap_or(dir).to_string_lossy().into_owned();
+
+    let mut guard = settings.0.lock().map_err(|e| e.to_string())?;
+    if !guard.kubeconfig_search_paths.contains(&dir) {
+        guard.kubeconfig_search_paths.push(dir);
+        guard.save(&app)?;
+    }
+    Ok(guard.kubeconfig_search_paths.clone())
+}
+
+/// The extra kubeconfig directories, in scan order (after `~/.kube`).
+#[tauri::command]
+pub async fn get_kubeconfig_search_paths(
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<String>, String> {
+    search_paths(&settings)
+}
+
+/// Returns the context named by `current-context` in the primary kubeconfig
+/// (`KUBECONFIG`'s first entry, else `~/.kube/config`), or None when unset.
+/// Cheap alternative to `get_kubeconfig_contexts` for rendering the header.
+#[tauri::command]
+pub async fn get_active_context() -> Result<Option<KubeContext>, String> {
+    let Some(path) = primary_kubeconfig_path().filter(|p| p.is_file()) else {
+        return Ok(None);
+    };
+
+    let cfg = Kubeconfig::read_from(&path)
+        .map_err(|e| format!("Failed to read kubeconfig: {e}"))?;
+
+    let Some(context_name) = cfg.current_context.clone() else {
+        return Ok(None);
+    };
+    let Some(ctx) = cfg
+        .contexts
+        .iter()
+        .find(|c| c.name == context_name)
+        .and_then(|c| c.context.as_ref())
+    else {
+        return Ok(None);
+    };
+
+    let server_url = cfg
+        .clusters
+        .iter()
+        .find(|c| c.name == ctx.cluster)
+        .and_then(|c| c.cluster.as_ref())
+        .and_then(|c| c.server.clone());
+
+    Ok(Some(KubeContext {
+        display_name: context_name.clone(),
+        context_name,
+        source_file: path.to_string_lossy().into_owned(),
+        cluster: ctx.cluster.clone(),
+        user: ctx.user.clone().unwrap_or_default(),
+        is_active: true,
+        server_url,
+    }))
+}
+
+/// Sets `current-context` in the kubeconfig at `path`, preserving all other
+/// fields verbatim by parsing as serde_yaml::Value.
+fn write_current_context(path: &Path, context_name: &str) -> Result<(), String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read kubeconfig: {e}"))?;
 
-            contexts.push(KubeContext {
-                display_name,
-                context_name,
-                source_file: source.clone(),
-                cluster: ctx.cluster.clone(),
-                user: ctx.user.clone().unwrap_or_default(),
-                is_active,
-                server_url,
-            });
+    let mut doc: serde_yaml::Value =
+        serde_yaml::from_str(&raw).map_err(|e| format!("Failed to parse kubeconfig: {e}"))?;
+
+    doc["current-context"] = serde_yaml::Value::String(context_name.to_string());
+
+    let updated = serde_yaml::to_string(&doc)
+        .map_err(|e| format!("Failed to serialize kubeconfig: {e}"))?;
+
+    std::fs::write(path, updated)
+        .map_err(|e| format!("Failed to write kubeconfig: {e}"))
+}
+
+/// Stops every exec session, log follow and watch so nothing keeps running
+/// against a cluster the user has switched away from — a shell left open on
+/// the old cluster is easy to mistake for one on the new cluster. Emits
+/// `session-invalidated` when anything was running.
+pub(crate) fn invalidate_sessions(app: &AppHandle, reason: String) {
+    let mut sessions = Vec::new();
+
+    if let Ok(mut killer) = app.state::<PtyKiller>().0.lock() {
+        if let Some((_, mut killer)) = killer.take() {
+            let _ = killer.kill();
+            sessions.push("exec".to_string());
         }
     }
+    if let Ok(mut writer) = app.state::<PtyState>().0.lock() {
+        *writer = None;
+    }
 
-    Ok(contexts)
+    let followers = app
+        .state::<PodLogStreams>()
+        .0
+        .lock()
+        .map(|mut streams| std::mem::take(&mut *streams))
+        .unwrap_or_default();
+    let children = app
+        .state::<LogStreams>()
+        .0
+        .lock()
+        .map(|mut streams| std::mem::take(&mut *streams))
+        .unwrap_or_default();
+    if !followers.is_empty() || !children.is_empty() {
+        sessions.push("logs".to_string());
+    }
+    for (id, mut follower) in followers {
+        follower.kill();
+        let _ = app.emit("pod-log-done", id);
+    }
+    for mut child in children {
+        let _ = child.start_kill();
+    }
+
+    if let Some(task) = app.state::<NamespaceWatch>().0.lock().ok().and_then(|mut w| w.take()) {
+        task.abort();
+        sessions.push("namespace-watch".to_string());
+    }
+    if let Some(mut child) = app.state::<RolloutWatch>().0.lock().ok().and_then(|mut w| w.take()) {
+        let _ = child.start_kill();
+        let _ = app.emit("rollout-done", false);
+        sessions.push("rollout".to_string());
+    }
+
+    if !sessions.is_empty() {
+        let _ = app.emit("session-invalidated", SessionInvalidated { reason, sessions });
+    }
 }
 
-/// Writes the new current-context into the source file for the selected context.
-/// Preserves all other fields verbatim by parsing as serde_yaml::Value.
+/// Makes `context_name` the current context by writing `current-context` into
+/// the file that defines it: `source_file` when given (it must contain the
+/// context), otherwise the file the context was found in by the scan.
+///
+/// When KUBECONFIG lists several files, kubectl takes `current-context` from
+/// the first file that sets one, so a write to a later file alone would not
+/// take effect. In that case the first KUBECONFIG file is updated as well;
+/// kubectl still resolves the context itself from the merged files.
+///
+/// Once the context is known to exist and the write succeeded, switching to
+/// a different context ends running exec, log and watch sessions (see
+/// `invalidate_sessions`) — also in read-only mode, where the kubeconfig write
+/// is refused but the app still switches. A failed switch leaves them running.
+/// "Different" is judged against the last context switched to (`SwitchedContext`),
+/// not the file, which read-only mode leaves stale.
+///
+/// Events emitted:
+/// - `session-invalidated` — payload: `SessionInvalidated` — sessions stopped
 #[tauri::command]
 pub async fn set_active_context(
+    app: AppHandle,
     context_name: String,
     source_file: Option<String>,
+    settings: State<'_, AppSettings>,
+    switched: State<'_, SwitchedContext>,
 ) -> Result<(), String> {
-    // Write to the specific source file if provided; otherwise fall back to
-    // the primary kubeconfig path.
-    let path = source_file
-        .map(PathBuf::from)
-        .or_else(primary_kubeconfig_path)
-        .ok_or_else(|| "Cannot determine kubeconfig path".to_string())?;
-
-    let raw = std::fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read kubeconfig: {e}"))?;
+    let from_file = get_active_context().await.ok().flatten().map(|c| c.context_name);
+    let invalidate = || {
+        let reason = switched
+            .0
+            .lock()
+            .map(|mut last| switch_reason(&mut last, from_file.clone(), &context_name));
+        if let Ok(Some(reason)) = reason {
+            invalidate_sessions(&app, reason);
+        }
+    };
+    let path = match source_file {
+        Some(file) => {
+            let path = PathBuf::from(file);
+            let cfg = Kubeconfig::read_from(&path)
+                .map_err(|e| format!("Failed to read kubeconfig: {e}"))?;
+            if !cfg.contexts.iter().any(|c| c.name == context_name) {
+                return Err(format!(
+                    "Context '{context_name}' is not defined in {}",
+                    path.display()
+                ));
+            }
+            path
+        }
+        None => scan_kubeconfig_contexts(&search_paths(&settings)?, true, None, None, false)?
+            .into_iter()
+            .find(|c| c.context_name == context_name)
+            .map(|c| PathBuf::from(c.source_file))
+            .ok_or_else(|| format!("Context '{context_name}' not found in any kubeconfig"))?,
+    };
 
-    let mut doc: serde_yaml::Value =
-        serde_yaml::from_str(&raw).map_err(|e| format!("Failed to parse kubeconfig: {e}"))?;
+    if let Err(e) = ensure_writable(&settings, "Switching the kubeconfig current-context") {
+        invalidate();
+        return Err(e);
+    }
+    write_current_context(&path, &context_name)?;
+
+    let env_files = kubeconfig_env_candidates();
+    if let Some((first, _)) = env_files.first() {
+        let owner_later = env_files.iter().skip(1).any(|(p, _)| same_file(p, &path));
+        if owner_later && !same_file(first, &path) {
+            write_current_context(first, &context_name)?;
+        }
+    }
+
+    invalidate();
+    Ok(())
+}
+
+/// Records `to` as the context switched to and, when that's a change, says
+/// why sessions end. The previous context is the last one switched to, or
+/// `from_file` (the kubeconfig's current-context) before the first switch.
+fn switch_reason(last: &mut Option<String>, from_file: Option<String>, to: &str) -> Option<String> {
+    let previous = last.replace(to.to_string()).or(from_file);
+    match previous {
+        Some(previous) if previous == to => None,
+        Some(previous) => Some(format!("Switched from context '{previous}' to '{to}'")),
+        None => Some(format!("Switched to context '{to}'")),
+    }
+}
 
-    doc["current-context"] = serde_yaml::Value::String(context_name);
+/// True when `a` and `b` name the same file (after resolving symlinks).
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b || a.canonicalize().ok().is_some_and(|ca| b.canonicalize().ok() == Some(ca))
+}
+
+// ── orphaned entries ──────────────────────────────────────────────────────────
+
+/// Cluster and user names referenced by the contexts of a kubeconfig document.
+fn context_references(doc: &serde_yaml::Value) -> (HashSet<String>, HashSet<String>) {
+    let mut clusters = HashSet::new();
+    let mut users = HashSet::new();
+    for ctx in doc["contexts"].as_sequence().into_iter().flatten() {
+        if let Some(cluster) = ctx["context"]["cluster"].as_str() {
+            clusters.insert(cluster.to_string());
+        }
+        if let Some(user) = ctx["context"]["user"].as_str() {
+            users.insert(user.to_string());
+        }
+    }
+    (clusters, users)
+}
+
+/// The clusters and users of `doc` that no context refers to, counting the
+/// contexts of `doc` itself and of the `others` kubectl merges with it.
+fn unreferenced_entries(
+    doc: &serde_yaml::Value,
+    others: &[serde_yaml::Value],
+) -> OrphanedKubeconfigEntries {
+    let (mut clusters, mut users) = context_references(doc);
+    for other in others {
+        let (c, u) = context_references(other);
+        clusters.extend(c);
+        users.extend(u);
+    }
+
+    let unreferenced = |section: &str, referenced: &HashSet<String>| -> Vec<String> {
+        doc[section]
+            .as_sequence()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry["name"].as_str())
+            .filter(|name| !referenced.contains(*name))
+            .map(String::from)
+            .collect()
+    };
+    OrphanedKubeconfigEntries {
+        clusters: unreferenced("clusters", &clusters),
+        users: unreferenced("users", &users),
+    }
+}
+
+fn read_kubeconfig_doc(path: &Path) -> Result<serde_yaml::Value, String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read kubeconfig {}: {e}", path.display()))?;
+    serde_yaml::from_str(&raw)
+        .map_err(|e| format!("Failed to parse kubeconfig {}: {e}", path.display()))
+}
+
+/// Parses `source_file` and finds its clusters and users no context refers
+/// to. Contexts from every file are considered: those in `source_file`
+/// itself and, when it is listed in KUBECONFIG, those in the other listed
+/// files — kubectl merges them, so they may point at entries here. Any of
+/// those files failing to read or parse is an error: its references would be
+/// unknown, and pruning could delete entries it still uses.
+fn orphaned_entries(
+    source_file: &str,
+) -> Result<(PathBuf, serde_yaml::Value, OrphanedKubeconfigEntries), String> {
+    let path = PathBuf::from(source_file);
+    let doc = read_kubeconfig_doc(&path)?;
 
+    let env_files = kubeconfig_env_candidates();
+    let others = match env_files.iter().any(|(p, _)| same_file(p, &path)) {
+        true => env_files
+            .iter()
+            .filter(|(p, _)| !same_file(p, &path))
+            .map(|(p, _)| read_kubeconfig_doc(p))
+            .collect::<Result<Vec<_>, _>>()?,
+        false => vec![],
+    };
+
+    let orphans = unreferenced_entries(&doc, &others);
+    Ok((path, doc, orphans))
+}
+
+/// Cluster and user entries in a kubeconfig file that no context references
+/// (see `orphaned_entries`).
+#[tauri::command]
+pub async fn find_orphaned_kubeconfig_entries(
+    source_file: String,
+) -> Result<OrphanedKubeconfigEntries, String> {
+    orphaned_entries(&source_file).map(|(_, _, orphans)| orphans)
+}
+
+/// Removes the entries `find_orphaned_kubeconfig_entries` reports and returns
+/// them. Other fields are kept verbatim (parsed as serde_yaml::Value); the
+/// previous version is copied to `<file>.bak` first. The file is left
+/// untouched when there is nothing to prune.
+#[tauri::command]
+pub async fn prune_orphaned_entries(
+    source_file: String,
+    settings: State<'_, AppSettings>,
+) -> Result<OrphanedKubeconfigEntries, String> {
+    ensure_writable(&settings, "Editing kubeconfig files")?;
+    let (path, mut doc, orphans) = orphaned_entries(&source_file)?;
+    if orphans.clusters.is_empty() && orphans.users.is_empty() {
+        return Ok(orphans);
+    }
+
+    for (section, names) in [("clusters", &orphans.clusters), ("users", &orphans.users)] {
+        if let Some(entries) = doc[section].as_sequence_mut() {
+            entries.retain(|e| !e["name"].as_str().is_some_and(|n| names.iter().any(|o| o == n)));
+        }
+    }
     let updated = serde_yaml::to_string(&doc)
         .map_err(|e| format!("Failed to serialize kubeconfig: {e}"))?;
 
-    std::fs::write(&path, updated)
-        .map_err(|e| format!("Failed to write kubeconfig: {e}"))?;
+    let mut backup = path.clone().into_os_string();
+    backup.push(".bak");
+    std::fs::copy(&path, &backup).map_err(|e| format!("Failed to back up kubeconfig: {e}"))?;
+    std::fs::write(&path, updated).map_err(|e| format!("Failed to write kubeconfig: {e}"))?;
+    Ok(orphans)
+}
 
-    Ok(())
+/// Returns the raw text of a kubeconfig file for the in-app editor.
+#[tauri::command]
+pub async fn get_kubeconfig_raw(source_file: String) -> Result<String, String> {
+    std::fs::read_to_string(&source_file).map_err(|e| format!("Failed to read kubeconfig: {e}"))
 }
 
-/// Pings the Kubernetes API server at `<server_url>/healthz` and returns:
-/// - "healthy"      — responded in < 1.5 s
-/// - "slow"         — responded in 1.5 – 5 s
-/// - "unreachable"  — timed out or connection refused
-#[tauri::command]
-pub async fn check_cluster_health(server_url: String) -> String {
-    let client = match reqwest::Client::builder()
-        .danger_accept_invalid_certs(true)
-        .timeout(Duration::from_secs(5))
-        .build()
-    {
+/// Replaces a kubeconfig file with `contents` after checking it parses as a
+/// kubeconfig. The previous version is copied to `<file>.bak` first.
+#[tauri::command]
+pub async fn set_kubeconfig_raw(
+    source_file: String,
+    contents: String,
+    settings: State<'_, AppSettings>,
+) -> Result<(), String> {
+    ensure_writable(&settings, "Editing kubeconfig files")?;
+    serde_yaml::from_str::<Kubeconfig>(&contents)
+        .map_err(|e| format!("Invalid kubeconfig: {e}"))?;
+
+    let path = PathBuf::from(&source_file);
+    if path.is_file() {
+        let mut backup = path.clone().into_os_string();
+        backup.push(".bak");
+        std::fs::copy(&path, &backup)
+            .map_err(|e| format!("Failed to back up kubeconfig: {e}"))?;
+    }
+
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write kubeconfig: {e}"))
+}
+
+/// Describes how the user bound to `context_name` authenticates, and for exec
+/// credential plugins whether the plugin binary is installed — so the UI can
+/// warn before switching instead of failing opaquely at proxy start.
+#[tauri::command]
+pub async fn context_auth_requirements(
+    context_name: String,
+    source_file: String,
+) -> Result<AuthRequirements, String> {
+    let kubeconfig = Kubeconfig::read_from(&source_file)
+        .map_err(|e| format!("Failed to read kubeconfig: {e}"))?;
+    let info = context_auth_info(&kubeconfig, &context_name)?;
+
+    let mut exec_command = None;
+    let auth_type = match info {
+        Some(i) if i.exec.is_some() => {
+            exec_command = i.exec.as_ref().and_then(|e| e.command.clone());
+            "exec"
+        }
+        Some(i) if i.auth_provider.as_ref().is_some_and(|p| p.name == "oidc") => "oidc",
+        Some(i) if i.client_certificate.is_some() || i.client_certificate_data.is_some() => {
+            "client-cert"
+        }
+        Some(i) if i.token.is_some() || i.token_file.is_some() => "token",
+        _ => "none",
+    };
+
+    let exec_command_found = exec_command
+        .as_deref()
+        .is_some_and(|cmd| which::which(cmd).is_ok());
+
+    Ok(AuthRequirements {
+        auth_type: auth_type.to_string(),
+        exec_command,
+        exec_command_found,
+    })
+}
+
+/// The cluster entry behind `context_name` — server, `proxy-url` and TLS
+/// settings — as written in `source_file`. Explains why `check_cluster_health`,
+/// which ignores `proxy-url` and the kubeconfig CA, can disagree with kubectl.
+#[tauri::command]
+pub async fn context_connection_details(
+    context_name: String,
+    source_file: String,
+) -> Result<ConnectionDetails, String> {
+    let kubeconfig = Kubeconfig::read_from(&source_file)
+        .map_err(|e| format!("Failed to read kubeconfig: {e}"))?;
+    let cluster_name = kubeconfig
+        .contexts
+        .iter()
+        .find(|c| c.name == context_name)
+        .and_then(|c| c.context.as_ref())
+        .map(|c| c.cluster.clone())
+        .ok_or_else(|| format!("Context '{context_name}' not found in kubeconfig"))?;
+    let cluster = kubeconfig
+        .clusters
+        .iter()
+        .find(|c| c.name == cluster_name)
+        .and_then(|c| c.cluster.as_ref())
+        .ok_or_else(|| format!("Cluster '{cluster_name}' not found in kubeconfig"))?;
+
+    Ok(ConnectionDetails {
+        server: cluster.server.clone(),
+        proxy_url: cluster.proxy_url.clone(),
+        insecure_skip_tls: cluster.insecure_skip_tls_verify.unwrap_or(false),
+        has_ca_data: cluster.certificate_authority_data.as_ref().is_some_and(|d| !d.is_empty()),
+        ca_file: cluster.certificate_authority.clone(),
+    })
+}
+
+/// Builds the HTTP client used for health checks.
+///
+/// Certificate verification is on unless `insecure` is set. When
+/// `CLUSTEROPS_CA_BUNDLE` points at a PEM file, its certificates are trusted
+/// in addition to the built-in roots (for gateways fronted by a private CA).
+/// `HTTPS_PROXY` / `HTTP_PROXY` / `NO_PROXY` are honoured via reqwest's
+/// system proxy support.
+fn health_client(insecure: bool, timeout: Duration) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder().timeout(timeout);
+
+    if insecure {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    if let Some(path) = std::env::var_os("CLUSTEROPS_CA_BUNDLE").filter(|v| !v.is_empty()) {
+        let pem = std::fs::read(&path)
+            .map_err(|e| format!("Failed to read CLUSTEROPS_CA_BUNDLE: {e}"))?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| format!("Failed to parse CLUSTEROPS_CA_BUNDLE: {e}"))?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// Probes `<server_url>/healthz` and returns the classification plus the
+/// round-trip latency in milliseconds (None when unreachable).
+async fn probe_health(
+    server_url: &str,
+    insecure: bool,
+    thresholds: HealthThresholds,
+) -> (String, Option<u64>) {
+    // A missing or malformed URL says nothing about the cluster itself, so it
+    // must not be reported as "unreachable".
+    let valid_url = reqwest::Url::parse(server_url.trim())
+        .is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.host_str().is_some());
+    if !valid_url {
+        return ("unknown".to_string(), None);
+    }
+
+    let client = match health_client(insecure, Duration::from_millis(thresholds.timeout_ms)) {
         Ok(c) => c,
-        Err(_) => return "unreachable".to_string(),
+        Err(e) => {
+            log::warn!("health check client for {server_url}: {e}");
+            return ("unreachable".to_string(), None);
+        }
     };
 
-    let url = format!("{}/healthz", server_url.trim_end_matches('/'));
+    let url = format!("{}/healthz", server_url.trim().trim_end_matches('/'));
     let started = Instant::now();
 
     match client.get(&url).send().await {
         Ok(_) => {
-            if started.elapsed() > Duration::from_millis(1500) {
-                "slow".to_string()
+            let elapsed = started.elapsed();
+            let health = if elapsed > Duration::from_millis(thresholds.slow_ms) {
+                "slow"
             } else {
-                "healthy".to_string()
+                "healthy"
+            };
+            (health.to_string(), Some(elapsed.as_millis() as u64))
+        }
+        Err(_) => ("unreachable".to_string(), None),
+    }
+}
+
+/// Pings the Kubernetes API server at `<server_url>/healthz` and returns:
+/// - "healthy"      — responded within `slowMs` (default 1.5 s)
+/// - "slow"         — responded between `slowMs` and `timeoutMs` (default 5 s)
+/// - "unreachable"  — timed out, connection refused, or TLS verification failed
+/// - "unknown"      — `server_url` is missing, empty or not an http(s) URL
+///
+/// The thresholds come from the `healthThresholds` setting.
+/// Pass `insecure: true` to skip certificate verification for self-signed clusters.
+/// Probes beyond the `maxConcurrentRequests` setting wait for a free slot.
+#[tauri::command]
+pub async fn check_cluster_health(
+    server_url: Option<String>,
+    insecure: bool,
+    settings: State<'_, AppSettings>,
+    limiter: State<'_, RequestLimiter>,
+) -> Result<String, String> {
+    let thresholds = settings.0.lock().map_err(|e| e.to_string())?.health_thresholds();
+    let _permit = request_limiter(&limiter)?.acquire_owned().await;
+    Ok(probe_health(server_url.as_deref().unwrap_or_default(), insecure, thresholds).await.0)
+}
+
+/// Vets a kubeconfig file without adding it to the scan path: parses it and
+/// health-checks every context's server (same classification as
+/// `check_cluster_health`, honouring the cluster's `insecure-skip-tls-verify`).
+/// Read-only — the file is never copied or merged. Contexts are probed
+/// concurrently, up to the `maxConcurrentRequests` setting.
+#[tauri::command]
+pub async fn test_kubeconfig_file(
+    path: String,
+    settings: State<'_, AppSettings>,
+    limiter: State<'_, RequestLimiter>,
+) -> Result<KubeconfigTest, String> {
+    let thresholds = settings.0.lock().map_err(|e| e.to_string())?.health_thresholds();
+    let limit = request_limiter(&limiter)?;
+    let path = PathBuf::from(path);
+    if !path.is_file() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+    let kubeconfig =
+        Kubeconfig::read_from(&path).map_err(|e| format!("Failed to parse kubeconfig: {e}"))?;
+
+    let handles: Vec<_> = kubeconfig
+        .contexts
+        .iter()
+        .map(|named| {
+            let cluster_name = named
+                .context
+                .as_ref()
+                .map(|c| c.cluster.clone())
+                .unwrap_or_default();
+            let cluster = kubeconfig
+                .clusters
+                .iter()
+                .find(|c| c.name == cluster_name)
+                .and_then(|c| c.cluster.as_ref());
+            let server = cluster.and_then(|c| c.server.clone()).unwrap_or_default();
+            let insecure = cluster.and_then(|c| c.insecure_skip_tls_verify).unwrap_or(false);
+            let name = named.name.clone();
+            let limit = limit.clone();
+
+            tokio::spawn(async move {
+                let _permit = limit.acquire_owned().await;
+                let (reachable, _) = probe_health(&server, insecure, thresholds).await;
+                ContextReachability { name, cluster: cluster_name, reachable }
+            })
+        })
+        .collect();
+
+    let mut contexts = Vec::with_capacity(handles.len());
+    for handle in handles {
+        contexts.push(handle.await.map_err(|e| e.to_string())?);
+    }
+    Ok(KubeconfigTest { contexts })
+}
+
+/// Upper bound on the version + pod count lookup for a single cluster.
+const DASHBOARD_CLUSTER_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Fetches server version and total pod count for one context.
+/// Pod count uses a `limit=1` list plus `remainingItemCount`, so it costs one
+/// tiny request regardless of cluster size.
+async fn cluster_version_and_pods(ctx: &KubeContext) -> Result<(String, Option<u64>), String> {
+    let client = build_context_client(&ctx.source_file, &ctx.context_name).await?;
+
+    let version = client
+        .apiserver_version()
+        .await
+        .map_err(|e| e.to_string())?
+        .git_version;
+
+    let api: Api<Pod> = Api::all(client);
+    let pod_count = api
+        .list_metadata(&ListParams::default().limit(1))
+        .await
+        .ok()
+        .and_then(|list| match list.metadata.remaining_item_count {
+            Some(rest) => Some(list.items.len() as u64 + rest.max(0) as u64),
+            None if list.metadata.continue_.is_none() => Some(list.items.len() as u64),
+            None => None,
+        });
+
+    Ok((version, pod_count))
+}
+
+async fn cluster_card(ctx: KubeContext, thresholds: HealthThresholds) -> ClusterCard {
+    let (health, latency_ms) = match ctx.server_url.as_deref() {
+        Some(url) => probe_health(url, false, thresholds).await,
+        None => ("unknown".to_string(), None),
+    };
+
+    let (server_version, pod_count) = if health == "unreachable" {
+        (None, None)
+    } else {
+        match tokio::time::timeout(DASHBOARD_CLUSTER_TIMEOUT, cluster_version_and_pods(&ctx)).await {
+            Ok(Ok((v, n))) => (Some(v), n),
+            _ => (None, None),
+        }
+    };
+
+    ClusterCard {
+        display_name: ctx.display_name,
+        context_name: ctx.context_name,
+        source_file: ctx.source_file,
+        health,
+        latency_ms,
+        server_version,
+        pod_count,
+    }
+}
+
+/// Returns one `ClusterCard` per kubeconfig context for the clusters overview.
+/// Up to `maxConcurrentRequests` clusters are probed at once and each lookup
+/// is time-boxed, so one dead cluster can't stall the whole dashboard.
+#[tauri::command]
+pub async fn clusters_dashboard(
+    settings: State<'_, AppSettings>,
+    limiter: State<'_, RequestLimiter>,
+) -> Result<Vec<ClusterCard>, String> {
+    let thresholds = settings.0.lock().map_err(|e| e.to_string())?.health_thresholds();
+    let contexts = scan_kubeconfig_contexts(&search_paths(&settings)?, false, None, None, false)?;
+    let limit = request_limiter(&limiter)?;
+
+    let handles: Vec<_> = contexts
+        .into_iter()
+        .map(|ctx| {
+            let limit = limit.clone();
+            tokio::spawn(async move {
+                let _permit = limit.acquire_owned().await;
+                cluster_card(ctx, thresholds).await
+            })
+        })
+        .collect();
+
+    let mut cards = Vec::with_capacity(handles.len());
+    for handle in handles {
+        cards.push(handle.await.map_err(|e| e.to_string())?);
+    }
+    Ok(cards)
+}
+
+// ── compare_namespaces ────────────────────────────────────────────────────────
+
+/// Deployments (with desired replicas) and standalone pods in one namespace of
+/// one context. Controller-owned pods are skipped: their names are random.
+async fn namespace_workloads(
+    ctx: &KubeContext,
+    namespace: &str,
+) -> Result<BTreeMap<(String, String), Option<i32>>, String> {
+    let client = build_context_client(&ctx.source_file, &ctx.context_name).await?;
+    let err = |e: kube::Error| format!("{}: {e}", ctx.display_name);
+
+    let mut workloads = BTreeMap::new();
+
+    let deployments: Api<Deployment> = Api::namespaced(client.clone(), namespace);
+    for d in deployments.list(&ListParams::default()).await.map_err(err)?.items {
+        let replicas = d.spec.and_then(|s| s.replicas).or(Some(1));
+        let name = d.metadata.name.unwrap_or_default();
+        workloads.insert(("Deployment".to_string(), name), replicas);
+    }
+
+    let pods: Api<Pod> = Api::namespaced(client, namespace);
+    for p in pods.list(&ListParams::default()).await.map_err(err)?.items {
+        if p.metadata.owner_references.as_ref().is_some_and(|o| !o.is_empty()) {
+            continue;
+        }
+        workloads.insert(("Pod".to_string(), p.metadata.name.unwrap_or_default()), None);
+    }
+
+    Ok(workloads)
+}
+
+/// Compares the workloads of `namespace` in two contexts (e.g. staging vs
+/// prod): what exists on only one side, and Deployments whose replica counts
+/// differ. The clusters are queried one after the other.
+#[tauri::command]
+pub async fn compare_namespaces(
+    context_a: KubeContext,
+    context_b: KubeContext,
+    namespace: String,
+) -> Result<NamespaceDiff, String> {
+    let a = namespace_workloads(&context_a, &namespace).await?;
+    let b = namespace_workloads(&context_b, &namespace).await?;
+
+    let to_ref = |(kind, name): &(String, String)| WorkloadRef {
+        kind: kind.clone(),
+        name: name.clone(),
+    };
+
+    let mut diff = NamespaceDiff {
+        namespace,
+        only_in_a: a.keys().filter(|k| !b.contains_key(*k)).map(to_ref).collect(),
+        only_in_b: b.keys().filter(|k| !a.contains_key(*k)).map(to_ref).collect(),
+        replica_differences: Vec::new(),
+    };
+
+    for (key, replicas_a) in &a {
+        if let Some(replicas_b) = b.get(key) {
+            if replicas_a != replicas_b {
+                diff.replica_differences.push(ReplicaDiff {
+                    kind: key.0.clone(),
+                    name: key.1.clone(),
+                    replicas_a: replicas_a.unwrap_or(0),
+                    replicas_b: replicas_b.unwrap_or(0),
+                });
             }
         }
-        Err(_) => "unreachable".to_string(),
+    }
+
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KUBECONFIG: &str = "\
+apiVersion: v1
+kind: Config
+clusters:
+- name: prod
+  cluster: {server: https://prod.example.com}
+- name: staging
+  cluster: {server: https://staging.example.com}
+- name: old
+  cluster: {server: https://old.example.com}
+users:
+- name: alice
+  user: {token: a}
+- name: ci
+  user: {token: c}
+contexts:
+- name: prod
+  context: {cluster: prod, user: alice}
+- name: broken
+  context: {namespace: default}
+current-context: prod
+";
+
+    fn yaml(raw: &str) -> serde_yaml::Value {
+        serde_yaml::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn context_references_collects_clusters_and_users() {
+        let (clusters, users) = context_references(&yaml(KUBECONFIG));
+        assert_eq!(clusters, HashSet::from(["prod".to_string()]));
+        assert_eq!(users, HashSet::from(["alice".to_string()]));
+        assert_eq!(context_references(&yaml("kind: Config")), (HashSet::new(), HashSet::new()));
+    }
+
+    #[test]
+    fn unreferenced_entries_are_orphans() {
+        let orphans = unreferenced_entries(&yaml(KUBECONFIG), &[]);
+        assert_eq!(orphans.clusters, ["staging", "old"]);
+        assert_eq!(orphans.users, ["ci"]);
+    }
+
+    #[test]
+    fn entries_used_by_merged_files_are_kept() {
+        let other = yaml("contexts:\n- name: ci\n  context: {cluster: staging, user: ci}\n");
+        let orphans = unreferenced_entries(&yaml(KUBECONFIG), &[other]);
+        assert_eq!(orphans.clusters, ["old"]);
+        assert!(orphans.users.is_empty());
+    }
+
+    #[test]
+    fn unreadable_kubeconfig_is_an_error() {
+        let dir = std::env::temp_dir().join(format!("orphans-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let broken = dir.join("broken");
+        std::fs::write(&broken, "contexts: [unclosed").unwrap();
+
+        assert!(read_kubeconfig_doc(&broken).unwrap_err().starts_with("Failed to parse"));
+        let missing = read_kubeconfig_doc(&dir.join("missing")).unwrap_err();
+        assert!(missing.starts_with("Failed to read"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn read_only_switch_back_still_invalidates() {
+        // Read-only mode never writes the file, so it keeps saying "a".
+        let from_file = || Some("a".to_string());
+        let mut last = None;
+
+        assert!(switch_reason(&mut last, from_file(), "b").is_some());
+        let back = switch_reason(&mut last, from_file(), "a");
+        assert_eq!(back.as_deref(), Some("Switched from context 'b' to 'a'"));
+        assert_eq!(switch_reason(&mut last, from_file(), "a"), None);
+    }
+
+    #[test]
+    fn first_switch_compares_against_the_file() {
+        let mut last = None;
+        assert_eq!(switch_reason(&mut last, Some("a".to_string()), "a"), None);
+        assert_eq!(last.as_deref(), Some("a"));
+
+        let mut last = None;
+        let reason = switch_reason(&mut last, None, "a");
+        assert_eq!(reason.as_deref(), Some("Switched to context 'a'"));
     }
 }
@@ -1,28 +1,276 @@
-use tauri::{AppHandle, Emitter};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use serde::Deserialize;
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 
+use crate::commands::pods::{
+    ensure_pod_uid, exec_shell_command, in_cluster_env, STREAM_HEARTBEAT_INTERVAL,
+};
+use crate::commands::settings::ensure_writable;
+use crate::models::k8s::{
+    ApiResourceInfo, DescribeSection, KubectlCapture, Prerequisites, TopUsage, VersionSkew,
+};
+use crate::settings::{validate_kubectl_global_args, DEFAULT_EXEC_SHELL};
+use crate::{ApiResourcesCache, AppSettings, RolloutWatch};
+
+// ── kubectl binary ────────────────────────────────────────────────────────────
+
+/// Overrides kubectl discovery with an explicit binary path.
+const KUBECTL_OVERRIDE_ENV: &str = "CLUSTEROPS_KUBECTL";
+
+/// Package-manager install directories checked on Linux / macOS.
+const UNIX_KUBECTL_DIRS: &[&str] = &["/usr/local/bin", "/opt/homebrew/bin", "/usr/bin", "/snap/bin"];
+
+/// Install locations checked when kubectl isn't on PATH — common when the app
+/// is launched from a desktop shortcut with a minimal PATH.
+fn known_kubectl_locations() -> Vec<PathBuf> {
+    let home = dirs::home_dir();
+    if cfg!(windows) {
+        let mut paths: Vec<PathBuf> = home
+            .iter()
+            .map(|h| h.join("development_tools").join("kubectl.exe"))
+            .collect();
+        paths.push(PathBuf::from(
+            "C:\\Program Files\\Docker\\Docker\\resources\\bin\\kubectl.exe",
+        ));
+        paths
+    } else {
+        let mut paths: Vec<PathBuf> = UNIX_KUBECTL_DIRS
+            .iter()
+            .map(|dir| PathBuf::from(dir).join("kubectl"))
+            .collect();
+        paths.extend(home.map(|h| h.join(".local").join("bin").join("kubectl")));
+        paths
+    }
+}
+
+/// Finds kubectl: `CLUSTEROPS_KUBECTL` when it names an existing file, then
+/// PATH (`kubectl.exe` before `kubectl` on Windows), then
+/// `known_kubectl_locations`. None when kubectl is nowhere to be found.
+pub(crate) fn resolve_kubectl() -> Option<PathBuf> {
+    resolve_kubectl_from(
+        std::env::var_os(KUBECTL_OVERRIDE_ENV),
+        std::env::var_os("PATH"),
+        &known_kubectl_locations(),
+        Path::is_file,
+    )
+}
+
+/// `resolve_kubectl` with its inputs passed in: the override variable, the
+/// PATH value, the fallback `candidates` and the file-existence check used
+/// for the override and the candidates. PATH is searched with
+/// `which::which_in`, which honours the executable bit and PATHEXT.
+fn resolve_kubectl_from(
+    override_path: Option<OsString>,
+    path_var: Option<OsString>,
+    candidates: &[PathBuf],
+    exists: impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
+    if let Some(path) = override_path
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .filter(|p| exists(p))
+    {
+        return Some(path);
+    }
+
+    let names: &[&str] = if cfg!(windows) { &["kubectl.exe", "kubectl"] } else { &["kubectl"] };
+    let cwd = std::env::current_dir().unwrap_or_default();
+    names
+        .iter()
+        .find_map(|name| which::which_in(name, path_var.as_ref(), &cwd).ok())
+        .or_else(|| candidates.iter().find(|p| exists(p)).cloned())
+}
+
+/// The kubectl every command spawns — see `resolve_kubectl`. Falls back to
+/// the bare name so spawn errors still mention kubectl.
+pub(crate) fn kubectl_binary() -> String {
+    resolve_kubectl()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|| if cfg!(windows) { "kubectl.exe" } else { "kubectl" }.to_string())
+}
+
+// ── impersonation ─────────────────────────────────────────────────────────────
+
+/// Builds `--as` / `--as-group` flags for kubectl impersonation.
+///
+/// Impersonation only works when the real kubeconfig user has the
+/// `impersonate` verb on the target users/groups/serviceaccounts; otherwise
+/// kubectl fails with a Forbidden error, which callers forward as-is.
+/// Values starting with `-` are rejected so they can't smuggle extra flags.
+pub(crate) fn impersonation_args(
+    as_user: Option<&str>,
+    as_groups: Option<&[String]>,
+) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+
+    if let Some(user) = as_user.filter(|u| !u.is_empty()) {
+        if user.starts_with('-') {
+            return Err(format!("Invalid impersonation user '{user}'"));
+        }
+        args.push(format!("--as={user}"));
+    }
+    for group in as_groups.unwrap_or_default() {
+        if group.is_empty() || group.starts_with('-') {
+            return Err(format!("Invalid impersonation group '{group}'"));
+        }
+        args.push(format!("--as-group={group}"));
+    }
+
+    Ok(args)
+}
+
+/// Adds a hint to kubectl errors caused by missing impersonate permission.
+pub(crate) fn explain_impersonation_error(err: &str) -> String {
+    if err.contains("cannot impersonate") {
+        format!("{err}\n(impersonation requires the 'impersonate' verb for your real user)")
+    } else {
+        err.to_string()
+    }
+}
+
+// ── global flags ──────────────────────────────────────────────────────────────
+
+/// The `kubectlGlobalArgs` setting (e.g. `--request-timeout=10s`, `-v=6`).
+/// Every kubectl invocation puts these before its subcommand. Re-validated
+/// here because the settings file may have been edited by hand.
+pub(crate) fn global_args(settings: &AppSettings) -> Result<Vec<String>, String> {
+    let args = settings
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .kubectl_global_args
+        .clone();
+    validate_kubectl_global_args(&args)?;
+    Ok(args)
+}
+
+// ── build_kubectl_command ─────────────────────────────────────────────────────
+
+/// Parameters of the kubectl-backed pod actions, named like the commands' own
+/// arguments so the UI can pass the same object to `build_kubectl_command`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct KubectlParams {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub source_file: String,
+    pub context_name: String,
+    pub container: Option<String>,
+    pub tail: Option<u32>,
+    pub follow: bool,
+    pub as_user: Option<String>,
+    pub as_groups: Option<Vec<String>>,
+    pub workdir: Option<String>,
+    pub env: Option<HashMap<String, String>>,
+    /// Shell started by "exec"; None means `DEFAULT_EXEC_SHELL`.
+    pub shell: Option<String>,
+}
+
+/// Builds kubectl's arguments (without the `kubectl` itself) for one of the
+/// pod actions: "logs", "describe", "exec" or "delete".
+pub(crate) fn kubectl_args(action: &str, p: &KubectlParams) -> Result<Vec<String>, String> {
+    let mut args: Vec<String> = match action {
+        "logs" => vec!["logs".into()],
+        "describe" => vec!["describe".into(), "pod".into()],
+        "exec" => vec!["exec".into(), "-it".into()],
+        "delete" => vec!["delete".into(), "pod".into()],
+        other => return Err(format!("Unknown kubectl action '{other}'")),
+    };
+
+    args.push(p.name.clone());
+    if let Some(ns) = p.namespace.as_deref().filter(|ns| !ns.is_empty()) {
+        args.push("-n".into());
+        args.push(ns.to_string());
+    }
+    args.push(format!("--kubeconfig={}", p.source_file));
+    args.push(format!("--context={}", p.context_name));
+
+    if matches!(action, "logs" | "exec") {
+        if let Some(c) = p.container.as_deref().filter(|c| !c.is_empty()) {
+            args.push("-c".into());
+            args.push(c.to_string());
+        }
+    }
+    if action == "logs" {
+        if p.follow {
+            args.push("-f".into());
+        }
+        if let Some(n) = p.tail {
+            args.push(format!("--tail={n}"));
+        }
+    }
+
+    args.extend(impersonation_args(p.as_user.as_deref(), p.as_groups.as_deref())?);
+
+    if action == "exec" {
+        args.push("--".into());
+        args.extend(exec_shell_command(
+            p.workdir.as_deref(),
+            p.env.as_ref(),
+            p.shell.as_deref().unwrap_or(DEFAULT_EXEC_SHELL),
+        )?);
+    }
+
+    Ok(args)
+}
+
+/// Returns the kubectl argv equivalent to a pod action, for "copy as kubectl"
+/// tooltips. `params` takes the same fields the action's command does.
+#[tauri::command]
+pub async fn build_kubectl_command(
+    action: String,
+    params: serde_json::Value,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<String>, String> {
+    let params: KubectlParams =
+        serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {e}"))?;
+    let mut argv = vec!["kubectl".to_string()];
+    argv.extend(global_args(&settings)?);
+    argv.extend(kubectl_args(&action, &params)?);
+    Ok(argv)
+}
+
 // ── describe_pod ──────────────────────────────────────────────────────────────
 
 /// Runs `kubectl describe pod <name> -n <namespace>` against the specific
 /// kubeconfig file and context for the active cluster.
+/// `as_user` / `as_groups` impersonate another identity (see `impersonation_args`).
+/// With `uid`, a pod recreated under the same name is refused (see `ensure_pod_uid`).
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn describe_pod(
     name: String,
     namespace: String,
     source_file: String,
     context_name: String,
+    as_user: Option<String>,
+    as_groups: Option<Vec<String>>,
+    uid: Option<String>,
+    settings: State<'_, AppSettings>,
 ) -> Result<String, String> {
-    let kubectl = which::which("kubectl")
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| "kubectl".to_string());
+    ensure_pod_uid(&source_file, &context_name, &namespace, &name, uid.as_deref()).await?;
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
+
+    let args = kubectl_args("describe", &KubectlParams {
+        name,
+        namespace: Some(namespace),
+        source_file,
+        context_name,
+        as_user,
+        as_groups,
+        ..Default::default()
+    })?;
 
     let output = Command::new(&kubectl)
-        .args([
-            "describe", "pod", &name,
-            "-n", &namespace,
-            &format!("--kubeconfig={source_file}"),
-            &format!("--context={context_name}"),
-        ])
+        .args(&global)
+        .args(&args)
         .output()
         .await
         .map_err(|e| format!("kubectl not found: {e}"))?;
@@ -31,10 +279,130 @@ pub async fn describe_pod(
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     } else {
         let err = String::from_utf8_lossy(&output.stderr);
-        Err(format!("kubectl: {}", err.trim()))
+        Err(format!("kubectl: {}", explain_impersonation_error(err.trim())))
     }
 }
 
+// ── describe_pod_stream ───────────────────────────────────────────────────────
+
+/// Streaming variant of `describe_pod` — emits output line-by-line so the UI
+/// can render the first sections of huge objects immediately. `uid` is
+/// checked as in `describe_pod`.
+///
+/// Events emitted:
+/// - `describe-line`  — payload: `String`  — one line of output
+/// - `describe-error` — payload: `String`  — kubectl stderr (on non-zero exit)
+/// - `describe-done`  — payload: `null`    — stream finished
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn describe_pod_stream(
+    app: AppHandle,
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+    as_user: Option<String>,
+    as_groups: Option<Vec<String>>,
+    uid: Option<String>,
+    settings: State<'_, AppSettings>,
+) -> Result<(), String> {
+    ensure_pod_uid(&source_file, &context_name, &namespace, &name, uid.as_deref()).await?;
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
+
+    let args = kubectl_args("describe", &KubectlParams {
+        name,
+        namespace: Some(namespace),
+        source_file,
+        context_name,
+        as_user,
+        as_groups,
+        ..Default::default()
+    })?;
+
+    let mut child = Command::new(&kubectl)
+        .args(&global)
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("kubectl not found: {e}"))?;
+
+    let stdout = child.stdout.take().ok_or("no stdout")?;
+    let mut lines = BufReader::new(stdout).lines();
+
+    while let Some(line) = lines.next_line().await.map_err(|e| e.to_string())? {
+        app.emit("describe-line", line).map_err(|e| e.to_string())?;
+    }
+
+    drop(lines);
+
+    let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        let err = String::from_utf8_lossy(&output.stderr);
+        let err = err.trim();
+        if !err.is_empty() {
+            app.emit("describe-error", explain_impersonation_error(err))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    app.emit("describe-done", ()).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ── describe_pod_structured ───────────────────────────────────────────────────
+
+/// Splits `kubectl describe` output into its top-level sections. A section
+/// starts at an unindented `Key:` line; its content is the text after the
+/// colon plus every following indented line, with two spaces of indent removed.
+fn parse_describe_sections(output: &str) -> Vec<DescribeSection> {
+    let mut sections: Vec<DescribeSection> = Vec::new();
+
+    for line in output.lines() {
+        let top_level = !line.starts_with(char::is_whitespace);
+        match line.split_once(':').filter(|_| top_level) {
+            Some((key, value)) if !key.is_empty() => sections.push(DescribeSection {
+                name: key.to_string(),
+                content: value.trim().to_string(),
+            }),
+            _ => {
+                let Some(section) = sections.last_mut() else {
+                    continue;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let line = line.strip_prefix("  ").unwrap_or(line);
+                if !section.content.is_empty() {
+                    section.content.push('\n');
+                }
+                section.content.push_str(line);
+            }
+        }
+    }
+
+    sections
+}
+
+/// `describe_pod`, split into top-level sections (Name, Namespace, Status,
+/// Containers, Conditions, Events, ...) in the order kubectl prints them, so
+/// the UI can render collapsible sections.
+#[tauri::command]
+pub async fn describe_pod_structured(
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<DescribeSection>, String> {
+    let output =
+        describe_pod(name, namespace, source_file, context_name, None, None, None, settings)
+            .await?;
+    Ok(parse_describe_sections(&output))
+}
+
 // ── get_pod_describe_for_security ────────────────────────────────────────────
 
 /// Alias for describe_pod used by the security scan feature.
@@ -45,8 +413,9 @@ pub async fn get_pod_describe_for_security(
     namespace: String,
     source_file: String,
     context_name: String,
+    settings: State<'_, AppSettings>,
 ) -> Result<String, String> {
-    describe_pod(name, namespace, source_file, context_name).await
+    describe_pod(name, namespace, source_file, context_name, None, None, None, settings).await
 }
 
 // ── get_network_scan_data ─────────────────────────────────────────────────────
@@ -56,10 +425,10 @@ pub async fn get_network_scan_data(
     namespace: String,
     source_file: String,
     context_name: String,
+    settings: State<'_, AppSettings>,
 ) -> Result<String, String> {
-    let kubectl = which::which("kubectl")
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| "kubectl".to_string());
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
 
     let kubeconfig = format!("--kubeconfig={source_file}");
     let context    = format!("--context={context_name}");
@@ -78,6 +447,7 @@ pub async fn get_network_scan_data(
         full_args.push(&context);
 
         if let Ok(output) = tokio::process::Command::new(&kubectl)
+            .args(&global)
             .args(&full_args)
             .output()
             .await
@@ -97,10 +467,10 @@ pub async fn get_rbac_scan_data(
     namespace: String,
     source_file: String,
     context_name: String,
+    settings: State<'_, AppSettings>,
 ) -> Result<String, String> {
-    let kubectl = which::which("kubectl")
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| "kubectl".to_string());
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
 
     let kubeconfig = format!("--kubeconfig={source_file}");
     let context    = format!("--context={context_name}");
@@ -119,6 +489,7 @@ pub async fn get_rbac_scan_data(
         full_args.push(&context);
 
         if let Ok(output) = tokio::process::Command::new(&kubectl)
+            .args(&global)
             .args(&full_args)
             .output()
             .await
@@ -138,10 +509,10 @@ pub async fn get_namespace_scan_data(
     namespace: String,
     source_file: String,
     context_name: String,
+    settings: State<'_, AppSettings>,
 ) -> Result<String, String> {
-    let kubectl = which::which("kubectl")
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| "kubectl".to_string());
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
 
     let kubeconfig = format!("--kubeconfig={source_file}");
     let context    = format!("--context={context_name}");
@@ -161,6 +532,7 @@ pub async fn get_namespace_scan_data(
         full_args.push(&context);
 
         if let Ok(output) = tokio::process::Command::new(&kubectl)
+            .args(&global)
             .args(&full_args)
             .output()
             .await
@@ -179,10 +551,10 @@ pub async fn get_namespace_scan_data(
 pub async fn get_node_scan_data(
     source_file: String,
     context_name: String,
+    settings: State<'_, AppSettings>,
 ) -> Result<String, String> {
-    let kubectl = which::which("kubectl")
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| "kubectl".to_string());
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
 
     let kubeconfig = format!("--kubeconfig={source_file}");
     let context    = format!("--context={context_name}");
@@ -201,6 +573,7 @@ pub async fn get_node_scan_data(
         full_args.push(&context);
 
         if let Ok(output) = tokio::process::Command::new(&kubectl)
+            .args(&global)
             .args(&full_args)
             .output()
             .await
@@ -213,18 +586,461 @@ pub async fn get_node_scan_data(
     Ok(combined)
 }
 
+// ── check_prerequisites ───────────────────────────────────────────────────────
+
+/// Reports whether kubectl is installed and whether the app is running
+/// in-cluster (where `connect_in_cluster` can be used instead of kubeconfig).
+#[tauri::command]
+pub async fn check_prerequisites() -> Prerequisites {
+    let kubectl_path = resolve_kubectl().map(|p| p.to_string_lossy().to_string());
+
+    Prerequisites {
+        kubectl_found: kubectl_path.is_some(),
+        kubectl_path,
+        in_cluster: in_cluster_env(),
+    }
+}
+
+// ── check_version_skew ────────────────────────────────────────────────────────
+
+/// Parses the minor version from a `kubectl version -o json` block.
+/// Managed clusters report values like "28+", so trailing non-digits are dropped.
+fn parse_minor(version: &serde_json::Value) -> Option<u32> {
+    let minor = version["minor"].as_str()?;
+    let digits: String = minor.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Runs `kubectl version -o json` and compares client vs server minor
+/// versions. kubectl supports one minor version of skew either way.
+#[tauri::command]
+pub async fn check_version_skew(
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+) -> Result<VersionSkew, String> {
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
+
+    let output = Command::new(&kubectl)
+        .args(&global)
+        .args([
+            "version", "-o", "json",
+            &format!("--kubeconfig={source_file}"),
+            &format!("--context={context_name}"),
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("kubectl not found: {e}"))?;
+
+    // kubectl exits non-zero when the server is unreachable but still prints
+    // the client block, so parse stdout regardless of the exit status.
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).map_err(|_| {
+        let err = String::from_utf8_lossy(&output.stderr);
+        format!("kubectl: {}", err.trim())
+    })?;
+
+    let client = &json["clientVersion"];
+    let server = &json["serverVersion"];
+    if server.is_null() {
+        let err = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("kubectl: could not reach server: {}", err.trim()));
+    }
+
+    let skew_ok = match (parse_minor(client), parse_minor(server)) {
+        (Some(c), Some(s)) => c.abs_diff(s) <= 1,
+        _ => false,
+    };
+
+    Ok(VersionSkew {
+        client: client["gitVersion"].as_str().unwrap_or_default().to_string(),
+        server: server["gitVersion"].as_str().unwrap_or_default().to_string(),
+        skew_ok,
+    })
+}
+
+// ── kubectl_top_pods / kubectl_top_nodes ─────────────────────────────────────
+
+/// Runs `kubectl top <kind> --no-headers` and returns its stdout, turning
+/// kubectl's metrics errors into a "metrics-server not available" message.
+async fn kubectl_top(
+    kind: &str,
+    extra: &[&str],
+    source_file: &str,
+    context_name: &str,
+    settings: &AppSettings,
+) -> Result<String, String> {
+    let kubectl = kubectl_binary();
+
+    let output = Command::new(&kubectl)
+        .args(global_args(settings)?)
+        .args(["top", kind, "--no-headers"])
+        .args(extra)
+        .arg(format!("--kubeconfig={source_file}"))
+        .arg(format!("--context={context_name}"))
+        .output()
+        .await
+        .map_err(|e| format!("kubectl not found: {e}"))?;
+
+    if !output.status.success() {
+        let err = String::from_utf8_lossy(&output.stderr);
+        let err = err.trim();
+        if err.contains("Metrics API not available") || err.contains("metrics.k8s.io") {
+            return Err(format!("metrics-server not available: {err}"));
+        }
+        return Err(err.to_string());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Pod CPU / memory from `kubectl top pods` — a fallback for clusters where
+/// the metrics API is awkward to reach directly.
+#[tauri::command]
+pub async fn kubectl_top_pods(
+    namespace: String,
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<TopUsage>, String> {
+    let stdout =
+        kubectl_top("pods", &["-n", &namespace], &source_file, &context_name, &settings).await?;
+
+    // NAME  CPU(cores)  MEMORY(bytes)
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            match cols.as_slice() {
+                [name, cpu, memory, ..] => Some(TopUsage {
+                    name: name.to_string(),
+                    cpu: cpu.to_string(),
+                    memory: memory.to_string(),
+                }),
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+/// Node CPU / memory from `kubectl top nodes`.
+#[tauri::command]
+pub async fn kubectl_top_nodes(
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<TopUsage>, String> {
+    let stdout = kubectl_top("nodes", &[], &source_file, &context_name, &settings).await?;
+
+    // NAME  CPU(cores)  CPU%  MEMORY(bytes)  MEMORY%
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            match cols.as_slice() {
+                [name, cpu, _, memory, ..] => Some(TopUsage {
+                    name: name.to_string(),
+                    cpu: cpu.to_string(),
+                    memory: memory.to_string(),
+                }),
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+// ── list_api_resources ────────────────────────────────────────────────────────
+
+/// Parses `kubectl api-resources -o wide`. Columns are located by their
+/// header offsets because SHORTNAMES is blank for many rows. VERBS is
+/// "[get list]" on recent kubectl and "get,list" on older releases.
+fn parse_api_resources(stdout: &str) -> Result<Vec<ApiResourceInfo>, String> {
+    let mut lines = stdout.lines();
+    let header = lines.next().ok_or("kubectl api-resources printed nothing")?;
+    let offset = |col: &str| {
+        header.find(col).ok_or_else(|| format!("Unexpected api-resources header: {header}"))
+    };
+    let starts = [
+        0,
+        offset("SHORTNAMES")?,
+        offset("APIVERSION")?,
+        offset("NAMESPACED")?,
+        offset("KIND")?,
+        offset("VERBS")?,
+    ];
+    let end = header.find("CATEGORIES").unwrap_or(usize::MAX);
+
+    let field = |line: &str, i: usize| -> String {
+        let from = starts[i].min(line.len());
+        let to = starts.get(i + 1).copied().unwrap_or(end).min(line.len()).max(from);
+        line.get(from..to).unwrap_or_default().trim().to_string()
+    };
+    let list = |value: String, sep: char| -> Vec<String> {
+        value
+            .trim_matches(|c| c == '[' || c == ']')
+            .split(sep)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(String::from)
+            .collect()
+    };
+
+    Ok(lines
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let verbs = field(line, 5);
+            let sep = if verbs.contains(',') { ',' } else { ' ' };
+            ApiResourceInfo {
+                name: field(line, 0),
+                shortnames: list(field(line, 1), ','),
+                api_version: field(line, 2),
+                namespaced: field(line, 3) == "true",
+                kind: field(line, 4),
+                verbs: list(verbs, sep),
+            }
+        })
+        .collect())
+}
+
+/// Resource types the cluster serves, from `kubectl api-resources -o wide`,
+/// including CRDs. Cached per kubeconfig file and context; pass `refresh` to
+/// re-run discovery (e.g. after installing an operator).
+#[tauri::command]
+pub async fn list_api_resources(
+    source_file: String,
+    context_name: String,
+    refresh: Option<bool>,
+    settings: State<'_, AppSettings>,
+    cache: State<'_, ApiResourcesCache>,
+) -> Result<Vec<ApiResourceInfo>, String> {
+    let key = (source_file, context_name);
+    if !refresh.unwrap_or(false) {
+        if let Some(hit) = cache.0.lock().map_err(|e| e.to_string())?.get(&key) {
+            return Ok(hit.clone());
+        }
+    }
+
+    let output = Command::new(kubectl_binary())
+        .args(global_args(&settings)?)
+        .args(["api-resources", "-o", "wide"])
+        .arg(format!("--kubeconfig={}", key.0))
+        .arg(format!("--context={}", key.1))
+        .output()
+        .await
+        .map_err(|e| format!("kubectl not found: {e}"))?;
+
+    // A group whose aggregated API is down makes kubectl exit non-zero after
+    // printing everything else; keep the partial list but don't cache it.
+    let resources = parse_api_resources(&String::from_utf8_lossy(&output.stdout));
+    if !output.status.success() {
+        let err = String::from_utf8_lossy(&output.stderr);
+        return match resources {
+            Ok(resources) if !resources.is_empty() => Ok(resources),
+            _ => Err(format!("kubectl: {}", err.trim())),
+        };
+    }
+
+    let resources = resources?;
+    cache.0.lock().map_err(|e| e.to_string())?.insert(key, resources.clone());
+    Ok(resources)
+}
+
+// ── watch_rollout ─────────────────────────────────────────────────────────────
+
+/// Workload kinds `kubectl rollout status` understands.
+const ROLLOUT_KINDS: &[&str] = &["deployment", "statefulset", "daemonset"];
+
+/// Follows `kubectl rollout status <kind>/<name> -w` until the rollout
+/// completes or fails. Only one watch runs at a time: starting another (or
+/// `stop_rollout`) kills the previous one.
+///
+/// Events emitted:
+/// - `rollout-line`  — payload: `String` — e.g. "2 of 3 updated replicas are available..."
+/// - `rollout-error` — payload: `String` — kubectl stderr (on non-zero exit)
+/// - `rollout-done`  — payload: `bool`   — true when the rollout succeeded
+/// - `watch-heartbeat` — payload: `"rollout"` — every `STREAM_HEARTBEAT_INTERVAL`
+///   while kubectl is running, even when it has nothing new to print
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn watch_rollout(
+    app: AppHandle,
+    kind: String,
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+    watch: State<'_, RolloutWatch>,
+    settings: State<'_, AppSettings>,
+) -> Result<(), String> {
+    let kind = kind.to_lowercase();
+    if !ROLLOUT_KINDS.contains(&kind.as_str()) {
+        return Err(format!("Unsupported rollout kind '{kind}'"));
+    }
+
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
+
+    let mut child = Command::new(&kubectl)
+        .args(&global)
+        .args([
+            "rollout", "status", &format!("{kind}/{name}"), "-w",
+            "-n", &namespace,
+            &format!("--kubeconfig={source_file}"),
+            &format!("--context={context_name}"),
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("kubectl not found: {e}"))?;
+
+    let stdout = child.stdout.take().ok_or("no stdout")?;
+    let child_id = child.id();
+    if let Some(mut previous) = watch.0.lock().map_err(|e| e.to_string())?.replace(child) {
+        let _ = previous.start_kill();
+    }
+
+    let mut lines = BufReader::new(stdout).lines();
+    let mut heartbeat = tokio::time::interval(STREAM_HEARTBEAT_INTERVAL);
+    loop {
+        let line = tokio::select! {
+            line = lines.next_line() => line.map_err(|e| e.to_string())?,
+            _ = heartbeat.tick() => {
+                // Only while our kubectl is still alive; a replaced or dead one
+                // shouldn't vouch for the watch.
+                let running = match watch.0.lock().map_err(|e| e.to_string())?.as_mut() {
+                    Some(c) if c.id() == child_id => matches!(c.try_wait(), Ok(None)),
+                    _ => false,
+                };
+                if running {
+                    app.emit("watch-heartbeat", "rollout").map_err(|e| e.to_string())?;
+                }
+                continue;
+            }
+        };
+        let Some(line) = line else { break };
+        app.emit("rollout-line", line).map_err(|e| e.to_string())?;
+    }
+
+    drop(lines);
+
+    // Take the child back unless stop_rollout or a newer watch replaced it.
+    let child = {
+        let mut guard = watch.0.lock().map_err(|e| e.to_string())?;
+        match guard.as_ref() {
+            Some(c) if c.id() == child_id => guard.take(),
+            _ => None,
+        }
+    };
+    let Some(child) = child else {
+        return Ok(());
+    };
+
+    let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let err = String::from_utf8_lossy(&output.stderr);
+        let err = err.trim();
+        if !err.is_empty() {
+            app.emit("rollout-error", err).map_err(|e| e.to_string())?;
+        }
+    }
+
+    app.emit("rollout-done", output.status.success()).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Cancels the running `watch_rollout`, emitting `rollout-done` with `false`.
+#[tauri::command]
+pub async fn stop_rollout(app: AppHandle, watch: State<'_, RolloutWatch>) -> Result<(), String> {
+    let child = watch.0.lock().map_err(|e| e.to_string())?.take();
+    if let Some(mut child) = child {
+        let _ = child.start_kill();
+        app.emit("rollout-done", false).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
 // ── run_kubectl ───────────────────────────────────────────────────────────────
 
+/// kubectl subcommands that change cluster state or reach into containers.
+const MUTATING_VERBS: &[&str] = &[
+    "apply", "create", "delete", "edit", "patch", "replace", "scale", "autoscale", "set",
+    "label", "annotate", "taint", "cordon", "uncordon", "drain", "expose", "run", "exec", "cp",
+    "attach", "debug", "rollout", "certificate", "config", "port-forward", "proxy",
+];
+
+/// Built-in kubectl subcommands that only read. A verb in neither list is a
+/// plugin (`kubectl-<verb>` on PATH), which can do anything, so it's treated
+/// as mutating — as is a flag value mistaken for the verb.
+const READ_ONLY_VERBS: &[&str] = &[
+    "get", "describe", "logs", "top", "explain", "events", "api-resources", "api-versions",
+    "version", "cluster-info", "diff", "wait", "auth", "kustomize", "completion", "plugin",
+    "options", "help",
+];
+
+/// `rollout` subcommands that only read.
+const READ_ONLY_ROLLOUT: &[&str] = &["status", "history"];
+
+/// kubectl flags whose value may be the next argument (`-n prod`) rather than
+/// attached (`-n=prod`, `--namespace=prod`).
+const VALUE_FLAGS: &[&str] = &[
+    "-n", "--namespace", "-l", "--selector", "-o", "--output", "-c", "--container", "-f",
+    "--filename", "-k", "--kustomize", "-s", "--server", "-v", "--v", "-L", "--label-columns",
+    "--context", "--kubeconfig", "--cluster", "--user", "--token", "--as", "--as-group",
+    "--as-uid", "--username", "--password", "--request-timeout", "--cache-dir",
+    "--certificate-authority", "--client-certificate", "--client-key", "--tls-server-name",
+    "--field-selector", "--sort-by", "--template", "--since", "--since-time", "--tail",
+    "--chunk-size", "--vmodule", "--profile", "--profile-output", "--log-flush-frequency",
+];
+
+/// The non-flag words of a kubectl argv, with the values of `VALUE_FLAGS`
+/// skipped: `-n prod delete pod x` gives `delete`, `pod`, `x`.
+fn kubectl_words(args: &[String]) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut args = args.iter().map(String::as_str);
+    while let Some(arg) = args.next() {
+        if VALUE_FLAGS.contains(&arg) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            words.push(arg);
+        }
+    }
+    words
+}
+
+/// True when `args` (kubectl's argv without the binary) would change something
+/// and must be refused in read-only mode.
+fn is_mutating_kubectl(args: &[String]) -> bool {
+    let words = kubectl_words(args);
+    match words.as_slice() {
+        [] => false,
+        ["rollout", rest @ ..] => !rest.first().is_some_and(|sub| READ_ONLY_ROLLOUT.contains(sub)),
+        ["auth", rest @ ..] => rest.first() == Some(&"reconcile"),
+        [verb, ..] => MUTATING_VERBS.contains(verb) || !READ_ONLY_VERBS.contains(verb),
+    }
+}
+
+/// Runs a free-form kubectl command typed into the command bar, streaming its
+/// output as `command-output-line` / `command-output-error` events.
+/// `as_user` / `as_groups` impersonate another identity (see `impersonation_args`).
+///
+/// With `capture`, no events are emitted; the (pipe-filtered) stdout, stderr
+/// and exit code are returned instead. `--kubeconfig` / `--context` are
+/// appended either way. Streaming mode returns None.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn run_kubectl(
     app: AppHandle,
     command: String,
     source_file: String,
     context_name: String,
-) -> Result<(), String> {
-    let kubectl = which::which("kubectl")
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| "kubectl".to_string());
+    as_user: Option<String>,
+    as_groups: Option<Vec<String>>,
+    capture: Option<bool>,
+    settings: State<'_, AppSettings>,
+) -> Result<Option<KubectlCapture>, String> {
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
 
     // Strip leading "kubectl" if present
     let cmd_body = command.trim()
@@ -243,13 +1059,19 @@ pub async fn run_kubectl(
         .split_whitespace()
         .map(|s| s.to_string())
         .collect();
+    if is_mutating_kubectl(&args) {
+        let verb = kubectl_words(&args).first().copied().unwrap_or_default();
+        ensure_writable(&settings, &format!("kubectl {verb}"))?;
+    }
     args.push(format!("--kubeconfig={source_file}"));
     args.push(format!("--context={context_name}"));
+    args.extend(impersonation_args(as_user.as_deref(), as_groups.as_deref())?);
 
     // Run kubectl directly
     let output = tokio::time::timeout(
         tokio::time::Duration::from_secs(30),
         tokio::process::Command::new(&kubectl)
+            .args(&global)
             .args(&args)
             .output()
     ).await
@@ -266,11 +1088,20 @@ pub async fn run_kubectl(
         stdout
     };
 
+    if capture.unwrap_or(false) {
+        return Ok(Some(KubectlCapture {
+            stdout: final_output,
+            stderr,
+            exit_code: output.status.code(),
+        }));
+    }
+
     for line in final_output.lines() {
         app.emit("command-output-line", line.to_string())
             .map_err(|e| e.to_string())?;
     }
     if !stderr.is_empty() {
+        let stderr = explain_impersonation_error(stderr.trim_end());
         for line in stderr.lines() {
             app.emit("command-output-error", line.to_string())
                 .map_err(|e| e.to_string())?;
@@ -278,7 +1109,7 @@ pub async fn run_kubectl(
     }
     app.emit("command-output-done", ())
         .map_err(|e| e.to_string())?;
-    Ok(())
+    Ok(None)
 }
 
 fn apply_pipe(input: &str, pipe_cmd: &str) -> String {
@@ -322,3 +1153,169 @@ fn apply_pipe(input: &str, pipe_cmd: &str) -> String {
     format!("{}\n\n[ClusterOps: '{}' not supported. Use: grep, grep -v, grep -i, tail -n, head -n, wc -l]",
         input, pipe_cmd)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(windows))]
+    #[test]
+    fn kubectl_lookup_order_is_override_then_path_then_known_locations() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("kubectl-lookup-{}", std::process::id()));
+        let (plain, bin) = (root.join("plain"), root.join("bin"));
+        for (dir, mode) in [(&plain, 0o644), (&bin, 0o755)] {
+            std::fs::create_dir_all(dir).unwrap();
+            let file = dir.join("kubectl");
+            std::fs::write(&file, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&file, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+        let on_path = bin.join("kubectl");
+
+        let known = [PathBuf::from("/opt/homebrew/bin/kubectl")];
+        let resolve = |override_path: Option<&str>, path: &[&Path], present: &[&str]| {
+            let path_var = std::env::join_paths(path).ok();
+            resolve_kubectl_from(override_path.map(OsString::from), path_var, &known, |p| {
+                present.iter().any(|f| Path::new(f) == p)
+            })
+        };
+        let nope = Path::new("/nope");
+        let full_path = [nope, plain.as_path(), bin.as_path()];
+        let present = ["/tmp/my-kubectl", "/opt/homebrew/bin/kubectl"];
+
+        let chosen = resolve(Some("/tmp/my-kubectl"), &full_path, &present);
+        assert_eq!(chosen, Some(PathBuf::from(present[0])));
+        // A missing or empty override falls through to PATH, which skips the
+        // kubectl without an executable bit.
+        assert_eq!(resolve(Some("/tmp/gone"), &full_path, &present), Some(on_path.clone()));
+        assert_eq!(resolve(Some(""), &full_path, &[]), Some(on_path));
+        let not_executable = [nope, plain.as_path()];
+        assert_eq!(resolve(None, &not_executable, &present[1..]), Some(known[0].clone()));
+        assert_eq!(resolve(None, &not_executable, &[]), None);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    const API_RESOURCES_WIDE: &str = "\
+NAME      SHORTNAMES  APIVERSION              NAMESPACED  KIND     VERBS              CATEGORIES
+bindings              v1                      true        Binding  [create]
+pods      po          v1                      true        Pod      [get list watch]   all
+nodes     no          v1                      false       Node     [get list]
+";
+
+    /// Older kubectl: comma-separated verbs and no CATEGORIES column.
+    const API_RESOURCES_OLD: &str = "\
+NAME      SHORTNAMES  APIVERSION              NAMESPACED  KIND     VERBS
+services  svc         v1                      true        Service  create,get,list
+leases                coordination.k8s.io/v1  true        Lease    get,list
+";
+
+    #[test]
+    fn parses_api_resources_with_blank_shortnames_and_bracketed_verbs() {
+        let resources = parse_api_resources(API_RESOURCES_WIDE).unwrap();
+        let names: Vec<_> = resources.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["bindings", "pods", "nodes"]);
+
+        let (bindings, pods, nodes) = (&resources[0], &resources[1], &resources[2]);
+        assert!(bindings.shortnames.is_empty());
+        assert_eq!(bindings.api_version, "v1");
+        assert_eq!(bindings.verbs, ["create"]);
+        assert_eq!(pods.shortnames, ["po"]);
+        assert_eq!((pods.kind.as_str(), pods.namespaced), ("Pod", true));
+        // CATEGORIES ("all") isn't part of VERBS.
+        assert_eq!(pods.verbs, ["get", "list", "watch"]);
+        assert!(!nodes.namespaced);
+        assert_eq!(nodes.verbs, ["get", "list"]);
+    }
+
+    #[test]
+    fn parses_api_resources_with_comma_verbs_and_no_categories() {
+        let resources = parse_api_resources(API_RESOURCES_OLD).unwrap();
+        let (services, leases) = (&resources[0], &resources[1]);
+        assert_eq!(services.shortnames, ["svc"]);
+        assert_eq!(services.verbs, ["create", "get", "list"]);
+        assert!(leases.shortnames.is_empty());
+        assert_eq!(leases.api_version, "coordination.k8s.io/v1");
+        assert_eq!((leases.kind.as_str(), leases.namespaced), ("Lease", true));
+        assert_eq!(leases.verbs, ["get", "list"]);
+    }
+
+    #[test]
+    fn api_resources_needs_the_expected_header() {
+        assert!(parse_api_resources("").is_err());
+        assert!(parse_api_resources("NAME  KIND\npods  Pod\n").is_err());
+    }
+
+    fn argv(cmd: &str) -> Vec<String> {
+        cmd.split_whitespace().map(String::from).collect()
+    }
+
+    const DESCRIBE: &str = "\
+Name:             web-7d4b9c
+Namespace:        default
+Labels:           app=web
+                  pod-template-hash=7d4b9c
+Containers:
+  nginx:
+    Image:          nginx:1.25
+    Limits:
+      memory:  128Mi
+
+Events:
+  Type    Reason   Age   From     Message
+  ----    ------   ----  ----     -------
+  Normal  Pulled   2m    kubelet  Container image \"nginx:1.25\" already present
+";
+
+    #[test]
+    fn describe_sections_keep_order_and_inline_values() {
+        let sections = parse_describe_sections(DESCRIBE);
+        let names: Vec<&str> = sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Name", "Namespace", "Labels", "Containers", "Events"]);
+        assert_eq!(sections[0].content, "web-7d4b9c");
+        assert_eq!(sections[2].content, "app=web\n                pod-template-hash=7d4b9c");
+    }
+
+    #[test]
+    fn describe_sections_keep_nested_indentation() {
+        let sections = parse_describe_sections(DESCRIBE);
+        assert_eq!(
+            sections[3].content,
+            "nginx:\n  Image:          nginx:1.25\n  Limits:\n    memory:  128Mi"
+        );
+    }
+
+    #[test]
+    fn describe_events_table_stays_in_one_section() {
+        let events = parse_describe_sections(DESCRIBE).pop().unwrap();
+        let rows: Vec<&str> = events.content.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with("Type    Reason"));
+        assert!(rows[2].contains("already present"));
+
+        let none = parse_describe_sections("Events:  <none>\n");
+        assert_eq!(none[0].content, "<none>");
+    }
+
+    #[test]
+    fn mutating_verb_after_value_flags_is_found() {
+        assert!(is_mutating_kubectl(&argv("-n prod delete pod x")));
+        assert!(is_mutating_kubectl(&argv("--context foo -l app=x delete pods")));
+        assert!(is_mutating_kubectl(&argv("-o yaml apply -f x.yaml")));
+        assert!(!is_mutating_kubectl(&argv("-n prod get pods -o wide")));
+        assert!(!is_mutating_kubectl(&argv("--namespace=prod get pods")));
+    }
+
+    #[test]
+    fn tunnels_plugins_and_subcommands_are_classified() {
+        assert!(is_mutating_kubectl(&argv("port-forward pod/x 8080:80")));
+        assert!(is_mutating_kubectl(&argv("proxy")));
+        assert!(is_mutating_kubectl(&argv("neat get pod x")));
+        assert!(is_mutating_kubectl(&argv("rollout restart deploy/x")));
+        assert!(!is_mutating_kubectl(&argv("rollout status deploy/x")));
+        assert!(is_mutating_kubectl(&argv("auth reconcile -f rbac.yaml")));
+        assert!(!is_mutating_kubectl(&argv("auth can-i delete pods")));
+        assert!(!is_mutating_kubectl(&argv("")));
+    }
+}
@@ -1,73 +1,862 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use tauri::{AppHandle, Emitter};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
+use chrono::{DateTime, FixedOffset};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use regex::Regex;
+use k8s_openapi::api::core::v1::Pod;
+use kube::Api;
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::time::{sleep, sleep_until, Duration, Instant};
+
+use crate::commands::kubectl::{
+    explain_impersonation_error, global_args, kubectl_args, kubectl_binary, KubectlParams,
+};
+use crate::commands::pods::{
+    build_context_client, ensure_pod_uid, take_complete_utf8, STREAM_HEARTBEAT_INTERVAL,
+};
+use crate::models::k8s::ContainerLogLine;
+use crate::{AppSettings, LogStreams, PodLogStreams};
+
+/// Consecutive failed reconnects before a follow stream gives up.
+const MAX_RECONNECT_ATTEMPTS: u32 = 3;
+
+/// Minimum gap between `pod-log-filtered-count` events.
+const FILTERED_COUNT_INTERVAL: Duration = Duration::from_secs(1);
+
+static NEXT_STREAM_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The kubectl child currently serving a `get_pod_logs` stream.
+pub struct LogFollower {
+    /// "<context>/<namespace>/<pod>" — a new follow of the same pod replaces it.
+    pod: String,
+    /// None between a child exiting and its reconnect being spawned; the entry
+    /// stays so a stop in that window still ends the stream.
+    child: Option<Child>,
+}
+
+impl LogFollower {
+    pub(crate) fn kill(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.start_kill();
+        }
+    }
+}
+
+/// File that a `get_pod_logs` stream copies its emitted lines into.
+enum LogSink {
+    Plain(BufWriter<File>),
+    /// Compressed as lines arrive, so a long follow never sits in memory.
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl LogSink {
+    /// Creates `path`; with `compress`, a `.gz` suffix is added if missing.
+    fn create(path: &str, compress: bool) -> Result<Self, String> {
+        let path = if compress && !path.ends_with(".gz") {
+            format!("{path}.gz")
+        } else {
+            path.to_string()
+        };
+        let file = BufWriter::new(
+            File::create(&path).map_err(|e| format!("Failed to create {path}: {e}"))?,
+        );
+        Ok(if compress {
+            LogSink::Gzip(GzEncoder::new(file, Compression::default()))
+        } else {
+            LogSink::Plain(file)
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.out(), "{line}").map_err(|e| format!("Failed to write log file: {e}"))
+    }
+
+    /// Writes a piece of an over-long line, without a newline, so the file
+    /// keeps the line whole.
+    fn write_part(&mut self, part: &str) -> Result<(), String> {
+        self.out().write_all(part.as_bytes()).map_err(|e| format!("Failed to write log file: {e}"))
+    }
+
+    fn out(&mut self) -> &mut dyn Write {
+        match self {
+            LogSink::Plain(w) => w,
+            LogSink::Gzip(w) => w,
+        }
+    }
+
+    /// Flushes the file and, for gzip, writes the trailer.
+    fn finish(self) -> Result<(), String> {
+        let result = match self {
+            LogSink::Plain(mut w) => w.flush(),
+            LogSink::Gzip(w) => w.finish().and_then(|mut w| w.flush()),
+        };
+        result.map_err(|e| format!("Failed to finish log file: {e}"))
+    }
+}
+
+/// Appended to every piece of a split line except the last.
+const SPLIT_LINE_MARKER: &str = " …[truncated, continues]";
+
+/// Reads the next line like `lines()`, but without buffering more than `max`
+/// bytes: a longer line comes back in pieces, each flagged `true` while more
+/// of the line follows. Pieces end on a UTF-8 character boundary; invalid
+/// UTF-8 is replaced rather than ending the stream. `pending` carries a
+/// partial line between calls, which makes this safe to use in `select!`.
+async fn next_capped_line<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    max: usize,
+    pending: &mut Vec<u8>,
+) -> std::io::Result<Option<(String, bool)>> {
+    let text = |bytes: Vec<u8>| String::from_utf8_lossy(&bytes).into_owned();
+    loop {
+        let buf = reader.fill_buf().await?;
+        if buf.is_empty() {
+            if pending.is_empty() {
+                return Ok(None);
+            }
+            return Ok(Some((text(std::mem::take(pending)), false)));
+        }
+
+        // Up to two bytes past the cap so a line of exactly `max` bytes isn't
+        // split for its "\n" or "\r\n".
+        let room = max.saturating_sub(pending.len());
+        let window = &buf[..buf.len().min(room + 2)];
+        let newline = window.iter().position(|b| *b == b'\n').filter(|&i| {
+            let cr = i > 0 && window[i - 1] == b'\r';
+            i - usize::from(cr) <= room
+        });
+        if let Some(i) = newline {
+            pending.extend_from_slice(&buf[..i]);
+            reader.consume(i + 1);
+            if pending.last() == Some(&b'\r') {
+                pending.pop();
+            }
+            return Ok(Some((text(std::mem::take(pending)), false)));
+        }
+
+        let n = buf.len().min(room);
+        pending.extend_from_slice(&buf[..n]);
+        reader.consume(n);
+        if pending.len() >= max {
+            // A character cut off at the cap stays in `pending` for the next piece.
+            return Ok(Some((take_complete_utf8(pending), true)));
+        }
+    }
+}
+
+/// Splits a `--timestamps` log line into its RFC3339 timestamp and message.
+fn split_timestamp(line: &str) -> Option<(DateTime<FixedOffset>, &str)> {
+    let (ts, rest) = line.split_once(' ')?;
+    let ts = DateTime::parse_from_rfc3339(ts).ok()?;
+    Some((ts, rest))
+}
 
 // ── get_pod_logs ──────────────────────────────────────────────────────────────
 
 /// Streams pod logs line-by-line via Tauri events.
 ///
+/// `namespace` falls back to the default namespace (`set_default_namespace`)
+/// and then to the context's own namespace when omitted.
+///
+/// `container` selects a container with `-c`; init container names work too,
+/// including ones that have already completed.
+/// `as_user` / `as_groups` impersonate another identity (see `impersonation_args`).
+///
+/// With `follow` and `auto_reconnect`, a `kubectl logs -f` that dies with an
+/// error (e.g. a VPN blip) is re-spawned with `--since-time` set to the last
+/// line's timestamp; lines at or before it are dropped so nothing repeats.
+/// Gives up after `MAX_RECONNECT_ATTEMPTS` consecutive failures.
+///
+/// `grep` keeps only lines matching the regex (`grep_invert`: only lines that
+/// don't); the number suppressed so far is reported at most once a second and
+/// when the stream ends.
+///
+/// With `uid`, a pod recreated under the same name is refused up front (see
+/// `ensure_pod_uid`), and a reconnect stops instead of following the new pod.
+///
+/// `save_to` also writes every emitted line to that file (`compress`: gzip,
+/// `.gz` appended if missing); it is finalized when the stream ends or is
+/// stopped.
+///
+/// Lines longer than the `maxLogLineBytes` setting (64 KiB by default) are
+/// emitted in pieces, each but the last ending in `SPLIT_LINE_MARKER`, so a
+/// giant stack trace or base64 blob can't balloon memory or one event. The
+/// saved file keeps such lines whole.
+///
+/// `max_duration_secs` ends a follow that long after it starts — reconnects
+/// included — as if `stop_pod_logs` were called. Ignored without `follow`.
+///
+/// Each call gets a stream id, announced by `pod-log-started` and returned
+/// when the stream ends; `stop_pod_logs(stream_id)` kills it. Starting a new
+/// follow for the same pod kills the previous one.
+///
 /// Events emitted:
-/// - `pod-log-line`  — payload: `String`  — one line of output
-/// - `pod-log-error` — payload: `String`  — kubectl stderr (on non-zero exit)
-/// - `pod-log-done`  — payload: `null`    — stream finished
+/// - `pod-log-started`        — payload: `u64`    — stream id, before any output
+/// - `pod-log-line`           — payload: `String` — one line of output
+/// - `pod-log-reconnected`    — payload: `u32`    — reconnect attempt number
+/// - `pod-log-filtered-count` — payload: `u64`    — lines suppressed by `grep` so far
+/// - `pod-log-error`          — payload: `String` — kubectl stderr (on non-zero exit)
+/// - `pod-log-heartbeat`      — payload: `u64`    — stream id, every
+///   `STREAM_HEARTBEAT_INTERVAL` while a follow's kubectl is running, even when quiet
+/// - `pod-log-done`           — payload: `u64`    — stream id; the stream
+///   finished, was stopped, or was replaced by a newer follow of the pod
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn get_pod_logs(
     app: AppHandle,
     name: String,
-    namespace: String,
+    namespace: Option<String>,
     source_file: String,
     context_name: String,
+    container: Option<String>,
     tail: Option<u32>,
     follow: bool,
-) -> Result<(), String> {
-    let kubectl = which::which("kubectl")
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| "kubectl".to_string());
+    as_user: Option<String>,
+    as_groups: Option<Vec<String>>,
+    auto_reconnect: Option<bool>,
+    grep: Option<String>,
+    grep_invert: Option<bool>,
+    save_to: Option<String>,
+    compress: Option<bool>,
+    uid: Option<String>,
+    max_duration_secs: Option<u64>,
+    settings: State<'_, AppSettings>,
+    streams: State<'_, PodLogStreams>,
+) -> Result<u64, String> {
+    let deadline = max_duration_secs
+        .filter(|_| follow)
+        .map(|secs| Instant::now() + Duration::from_secs(secs));
+    let mut sink = save_to
+        .filter(|p| !p.is_empty())
+        .map(|p| LogSink::create(&p, compress.unwrap_or(false)))
+        .transpose()?;
+    let grep = grep
+        .filter(|g| !g.is_empty())
+        .map(|g| Regex::new(&g).map_err(|e| format!("Invalid grep pattern: {e}")))
+        .transpose()?;
+    let grep_invert = grep_invert.unwrap_or(false);
+
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
+    let max_line = settings.0.lock().map_err(|e| e.to_string())?.max_log_line_bytes();
 
-    let mut args = vec![
-        "logs".to_string(),
+    let reconnect = follow && auto_reconnect.unwrap_or(false);
+
+    let namespace = settings
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .namespace_or_default(namespace);
+
+    // (source_file, context_name, namespace, name, uid) for `ensure_pod_uid`.
+    let uid_check = match uid.filter(|u| !u.is_empty()) {
+        Some(uid) => {
+            let ns = namespace.clone().ok_or("uid needs an explicit namespace")?;
+            Some((source_file.clone(), context_name.clone(), ns, name.clone(), uid))
+        }
+        None => None,
+    };
+    if let Some((sf, ctx, ns, pod_name, uid)) = &uid_check {
+        ensure_pod_uid(sf, ctx, ns, pod_name, Some(uid)).await?;
+    }
+
+    let stream_id = NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed);
+    let pod = format!(
+        "{context_name}/{}/{name}",
+        namespace.as_deref().unwrap_or_default()
+    );
+
+    if follow {
+        replace_followers(&app, &streams, &pod)?;
+    }
+    streams
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(stream_id, LogFollower { pod: pod.clone(), child: None });
+    app.emit("pod-log-started", stream_id).map_err(|e| e.to_string())?;
+
+    // --tail is added per spawn below: a resumed stream uses --since-time instead.
+    let mut base_args = kubectl_args("logs", &KubectlParams {
         name,
+        namespace,
+        source_file,
+        context_name,
+        container,
+        follow,
+        as_user,
+        as_groups,
+        ..Default::default()
+    })?;
+    if reconnect {
+        // Needed to resume from the last line; stripped before emitting.
+        base_args.push("--timestamps".to_string());
+    }
+
+    let mut last_ts: Option<DateTime<FixedOffset>> = None;
+    let mut failures = 0;
+    let mut suppressed: u64 = 0;
+    let mut reported: u64 = 0;
+    let mut last_report = std::time::Instant::now();
+
+    loop {
+        let mut args = base_args.clone();
+        match last_ts {
+            Some(ts) => args.push(format!("--since-time={}", ts.to_rfc3339())),
+            None => {
+                if let Some(n) = tail {
+                    args.push(format!("--tail={n}"));
+                }
+            }
+        }
+
+        let mut child = Command::new(&kubectl)
+            .args(&global)
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("kubectl not found: {e}"))?;
+
+        let stdout = child.stdout.take().ok_or("no stdout")?;
+        // Gone from the map means stop_pod_logs or a newer follow ended the
+        // stream; the new child is killed on drop.
+        match streams.0.lock().map_err(|e| e.to_string())?.get_mut(&stream_id) {
+            Some(follower) => follower.child = Some(child),
+            None => return finish_stopped(sink, stream_id),
+        }
+
+        let mut lines = BufReader::new(stdout);
+        let mut partial = Vec::new();
+        let mut continuing = false;
+        let mut got_line = false;
+        let mut heartbeat = tokio::time::interval(STREAM_HEARTBEAT_INTERVAL);
+        let mut timed_out = false;
+
+        loop {
+            let line = tokio::select! {
+                line = next_capped_line(&mut lines, max_line, &mut partial) => {
+                    line.map_err(|e| e.to_string())?
+                }
+                _ = heartbeat.tick(), if follow => {
+                    if follower_running(&streams, stream_id)? {
+                        app.emit("pod-log-heartbeat", stream_id).map_err(|e| e.to_string())?;
+                    }
+                    continue;
+                }
+                _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    timed_out = true;
+                    break;
+                }
+            };
+            let Some((line, continues)) = line else { break };
+            // Only a line's first piece carries the --timestamps prefix.
+            let first_piece = !std::mem::replace(&mut continuing, continues);
+            let line = match split_timestamp(&line).filter(|_| reconnect && first_piece) {
+                Some((ts, msg)) => {
+                    // --since-time has second precision, so a resumed stream
+                    // replays part of the last second.
+                    if last_ts.is_some_and(|last| ts <= last) {
+                        continue;
+                    }
+                    last_ts = Some(ts);
+                    msg.to_string()
+                }
+                None => line,
+            };
+            got_line = true;
+            if let Some(re) = &grep {
+                if re.is_match(&line) == grep_invert {
+                    suppressed += 1;
+                    if last_report.elapsed() >= FILTERED_COUNT_INTERVAL {
+                        app.emit("pod-log-filtered-count", suppressed)
+                            .map_err(|e| e.to_string())?;
+                        reported = suppressed;
+                        last_report = std::time::Instant::now();
+                    }
+                    continue;
+                }
+            }
+            let written = sink.as_mut().map(|sink| match continues {
+                true => sink.write_part(&line),
+                false => sink.write_line(&line),
+            });
+            if let Some(Err(e)) = written {
+                // Keep streaming to the UI; only the file copy stops.
+                app.emit("pod-log-error", e).map_err(|e| e.to_string())?;
+                sink = None;
+            }
+            let line = if continues { line + SPLIT_LINE_MARKER } else { line };
+            app.emit("pod-log-line", line).map_err(|e| e.to_string())?;
+        }
+
+        drop(lines);
+
+        // Gone from the map means stop_pod_logs or a newer follow killed it.
+        let child = match streams.0.lock().map_err(|e| e.to_string())?.get_mut(&stream_id) {
+            Some(follower) => follower.child.take(),
+            None => return finish_stopped(sink, stream_id),
+        };
+        let Some(mut child) = child else { break };
+        if timed_out {
+            let _ = child.start_kill();
+            break;
+        }
+
+        let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
+
+        if output.status.success() {
+            break;
+        }
+
+        if reconnect {
+            if got_line {
+                failures = 0;
+            }
+            failures += 1;
+            if failures <= MAX_RECONNECT_ATTEMPTS {
+                sleep(Duration::from_secs(2)).await;
+                if !streams.0.lock().map_err(|e| e.to_string())?.contains_key(&stream_id) {
+                    return finish_stopped(sink, stream_id);
+                }
+                if deadline.is_some_and(|d| Instant::now() >= d) {
+                    break;
+                }
+                if let Some((sf, ctx, ns, pod_name, uid)) = &uid_check {
+                    if let Err(e) = ensure_pod_uid(sf, ctx, ns, pod_name, Some(uid)).await {
+                        app.emit("pod-log-error", e).map_err(|e| e.to_string())?;
+                        break;
+                    }
+                }
+                app.emit("pod-log-reconnected", failures)
+                    .map_err(|e| e.to_string())?;
+                continue;
+            }
+        }
+
+        let err = String::from_utf8_lossy(&output.stderr);
+        let err = err.trim();
+        if !err.is_empty() {
+            app.emit("pod-log-error", explain_impersonation_error(err))
+                .map_err(|e| e.to_string())?;
+        }
+        break;
+    }
+
+    streams.0.lock().map_err(|e| e.to_string())?.remove(&stream_id);
+    if suppressed != reported {
+        app.emit("pod-log-filtered-count", suppressed)
+            .map_err(|e| e.to_string())?;
+    }
+    if let Some(sink) = sink {
+        sink.finish()?;
+    }
+    app.emit("pod-log-done", stream_id).map_err(|e| e.to_string())?;
+    Ok(stream_id)
+}
+
+/// Kills and removes the streams following `pod` (a `LogFollower::pod` key)
+/// because a new follow replaces them, announcing each with `pod-log-done`.
+fn replace_followers(app: &AppHandle, streams: &PodLogStreams, pod: &str) -> Result<(), String> {
+    let mut replaced = Vec::new();
+    streams.0.lock().map_err(|e| e.to_string())?.retain(|id, f| {
+        if f.pod != pod {
+            return true;
+        }
+        f.kill();
+        replaced.push(*id);
+        false
+    });
+    for id in replaced {
+        app.emit("pod-log-done", id).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Whether the kubectl serving `stream_id` is still running. Checked before
+/// each heartbeat so a kubectl that died without closing stdout doesn't keep
+/// reporting the stream alive.
+fn follower_running(streams: &PodLogStreams, stream_id: u64) -> Result<bool, String> {
+    let mut guard = streams.0.lock().map_err(|e| e.to_string())?;
+    Ok(guard
+        .get_mut(&stream_id)
+        .and_then(|f| f.child.as_mut())
+        .is_some_and(|child| matches!(child.try_wait(), Ok(None))))
+}
+
+/// Ends a `get_pod_logs` stream that `stop_pod_logs`, a newer follow or
+/// `invalidate_sessions` already removed — and announced with `pod-log-done`
+/// carrying its id.
+fn finish_stopped(sink: Option<LogSink>, stream_id: u64) -> Result<u64, String> {
+    if let Some(sink) = sink {
+        sink.finish()?;
+    }
+    Ok(stream_id)
+}
+
+/// Kills the kubectl behind a `get_pod_logs` or `tail_pod_file` stream and
+/// emits `pod-log-done`.
+/// Unknown ids (already finished streams) are ignored.
+#[tauri::command]
+pub async fn stop_pod_logs(
+    app: AppHandle,
+    stream_id: u64,
+    streams: State<'_, PodLogStreams>,
+) -> Result<(), String> {
+    let follower = streams.0.lock().map_err(|e| e.to_string())?.remove(&stream_id);
+    if let Some(mut follower) = follower {
+        follower.kill();
+        app.emit("pod-log-done", stream_id).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// ── tail_pod_file ─────────────────────────────────────────────────────────────
+
+/// Lines of the file shown before following.
+const TAIL_FILE_LINES: u32 = 100;
+
+/// True when kubectl exec failed because `bin` isn't in the container image.
+fn missing_binary(stderr: &str, bin: &str) -> bool {
+    stderr.contains("executable file not found") && stderr.contains(&format!("\"{bin}\""))
+}
+
+/// Streams a file from inside a container — for apps that log to a file
+/// instead of stdout — via `kubectl exec -- tail -n 100 [-f] <path>`. Images
+/// without `tail` fall back to `cat`, which can't follow: the file is shown
+/// once and a `pod-log-error` explains why the stream ended.
+///
+/// Streams share ids, events, line splitting and `stop_pod_logs` with
+/// `get_pod_logs`; tailing the same file again replaces the previous follow.
+///
+/// Events emitted: `pod-log-started`, `pod-log-line`, `pod-log-error`,
+/// `pod-log-heartbeat` (while following) and `pod-log-done`, as for `get_pod_logs`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn tail_pod_file(
+    app: AppHandle,
+    pod: String,
+    namespace: String,
+    container: Option<String>,
+    path: String,
+    follow: bool,
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+    streams: State<'_, PodLogStreams>,
+) -> Result<u64, String> {
+    if path.trim().is_empty() {
+        return Err("path is required".to_string());
+    }
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
+    let max_line = settings.0.lock().map_err(|e| e.to_string())?.max_log_line_bytes();
+
+    let stream_id = NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed);
+    let key = format!("{context_name}/{namespace}/{pod}:{path}");
+    if follow {
+        replace_followers(&app, &streams, &key)?;
+    }
+    app.emit("pod-log-started", stream_id).map_err(|e| e.to_string())?;
+
+    let mut base_args = vec![
+        "exec".to_string(),
+        pod,
         "-n".to_string(),
         namespace,
         format!("--kubeconfig={source_file}"),
         format!("--context={context_name}"),
     ];
-
-    if let Some(n) = tail {
-        args.push(format!("--tail={n}"));
+    if let Some(c) = container.filter(|c| !c.is_empty()) {
+        base_args.push(format!("--container={c}"));
     }
+    base_args.push("--".to_string());
+
+    let lines_arg = TAIL_FILE_LINES.to_string();
+    let mut tail = vec!["tail", "-n", lines_arg.as_str()];
     if follow {
-        args.push("-f".to_string());
+        tail.push("-f");
     }
+    let attempts = [tail, vec!["cat"]];
 
-    let mut child = Command::new(&kubectl)
-        .args(&args)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("kubectl not found: {e}"))?;
-
-    let stdout = child.stdout.take().ok_or("no stdout")?;
-    let mut lines = BufReader::new(stdout).lines();
+    for (i, cmd) in attempts.iter().enumerate() {
+        let mut child = Command::new(&kubectl)
+            .args(&global)
+            .args(&base_args)
+            .args(cmd)
+            .arg(&path)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("kubectl not found: {e}"))?;
 
-    while let Some(line) = lines.next_line().await.map_err(|e| e.to_string())? {
-        app.emit("pod-log-line", line).map_err(|e| e.to_string())?;
-    }
+        let stdout = child.stdout.take().ok_or("no stdout")?;
+        streams
+            .0
+            .lock()
+            .map_err(|e| e.to_string())?
+            .insert(stream_id, LogFollower { pod: key.clone(), child: Some(child) });
 
-    drop(lines);
+        let mut lines = BufReader::new(stdout);
+        let mut partial = Vec::new();
+        let mut heartbeat = tokio::time::interval(STREAM_HEARTBEAT_INTERVAL);
+        loop {
+            let line = tokio::select! {
+                line = next_capped_line(&mut lines, max_line, &mut partial) => {
+                    line.map_err(|e| e.to_string())?
+                }
+                _ = heartbeat.tick(), if follow => {
+                    if follower_running(&streams, stream_id)? {
+                        app.emit("pod-log-heartbeat", stream_id).map_err(|e| e.to_string())?;
+                    }
+                    continue;
+                }
+            };
+            let Some((line, continues)) = line else { break };
+            let line = if continues { line + SPLIT_LINE_MARKER } else { line };
+            app.emit("pod-log-line", line).map_err(|e| e.to_string())?;
+        }
+        drop(lines);
 
-    let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
+        // Gone from the map means stop_pod_logs or a newer tail killed it.
+        let follower = streams.0.lock().map_err(|e| e.to_string())?.remove(&stream_id);
+        let Some(child) = follower.and_then(|f| f.child) else {
+            return Ok(stream_id);
+        };
+        let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
+        if output.status.success() {
+            if i > 0 && follow {
+                let msg = "tail is not available in this container; showed the file without following";
+                app.emit("pod-log-error", msg).map_err(|e| e.to_string())?;
+            }
+            break;
+        }
 
-    if !output.status.success() {
         let err = String::from_utf8_lossy(&output.stderr);
         let err = err.trim();
+        if i == 0 && missing_binary(err, "tail") {
+            continue;
+        }
         if !err.is_empty() {
             app.emit("pod-log-error", err).map_err(|e| e.to_string())?;
         }
+        break;
     }
 
+    app.emit("pod-log-done", stream_id).map_err(|e| e.to_string())?;
+    Ok(stream_id)
+}
+
+// ── stream_pod_all_container_logs ─────────────────────────────────────────────
+
+/// Streams the logs of every container in a pod at once, one `kubectl logs`
+/// per container, so the UI can colour lines by container. Unlike
+/// `--all-containers`, lines are emitted as each container produces them.
+///
+/// The kubectl children are kept in `LogStreams` until they exit, so
+/// `stop_pod_log_streams` can end a follow stream. If one container's kubectl
+/// can't be started, the ones already running are killed before erroring.
+///
+/// Events emitted:
+/// - `pod-container-log-line` — payload: `ContainerLogLine` — one tagged line
+/// - `pod-log-error`          — payload: `String`           — "[container] kubectl stderr"
+/// - `pod-log-done`           — payload: `null`             — every container finished
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn stream_pod_all_container_logs(
+    app: AppHandle,
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+    tail: Option<u32>,
+    follow: bool,
+    streams: State<'_, LogStreams>,
+    settings: State<'_, AppSettings>,
+) -> Result<(), String> {
+    let client = build_context_client(&source_file, &context_name).await?;
+    let pod = Api::<Pod>::namespaced(client, &namespace)
+        .get(&name)
+        .await
+        .map_err(|e| e.to_string())?;
+    let containers: Vec<String> = pod
+        .spec
+        .map(|s| s.containers.into_iter().map(|c| c.name).collect())
+        .unwrap_or_default();
+
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
+
+    let mut readers: Vec<tokio::task::JoinHandle<()>> = Vec::new();
+    let mut started = Vec::new();
+    for container in containers {
+        let spawned = spawn_container_logs(
+            &kubectl,
+            &global,
+            &name,
+            &namespace,
+            &source_file,
+            &context_name,
+            &container,
+            tail,
+            follow,
+        );
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                for reader in &readers {
+                    reader.abort();
+                }
+                let mut guard = streams.0.lock().map_err(|e| e.to_string())?;
+                guard.retain_mut(|child| {
+                    if !child.id().is_some_and(|id| started.contains(&id)) {
+                        return true;
+                    }
+                    let _ = child.start_kill();
+                    false
+                });
+                return Err(e);
+            }
+        };
+        started.extend(child.id());
+
+        let stdout = child.stdout.take().ok_or("no stdout")?;
+        let mut stderr = child.stderr.take().ok_or("no stderr")?;
+        streams.0.lock().map_err(|e| e.to_string())?.push(child);
+
+        let app = app.clone();
+        readers.push(tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let payload = ContainerLogLine { container: container.clone(), line };
+                let _ = app.emit("pod-container-log-line", payload);
+            }
+
+            let mut err = String::new();
+            let _ = stderr.read_to_string(&mut err).await;
+            let err = err.trim();
+            if !err.is_empty() {
+                let _ = app.emit("pod-log-error", format!("[{container}] {err}"));
+            }
+        }));
+    }
+
+    for reader in readers {
+        let _ = reader.await;
+    }
+
+    // Drop children that have exited; ones from other running streams stay.
+    streams
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+
     app.emit("pod-log-done", ()).map_err(|e| e.to_string())?;
     Ok(())
 }
+
+/// Spawns `kubectl logs` for one container of `stream_pod_all_container_logs`.
+#[allow(clippy::too_many_arguments)]
+fn spawn_container_logs(
+    kubectl: &str,
+    global: &[String],
+    name: &str,
+    namespace: &str,
+    source_file: &str,
+    context_name: &str,
+    container: &str,
+    tail: Option<u32>,
+    follow: bool,
+) -> Result<Child, String> {
+    let args = kubectl_args("logs", &KubectlParams {
+        name: name.to_string(),
+        namespace: Some(namespace.to_string()),
+        source_file: source_file.to_string(),
+        context_name: context_name.to_string(),
+        container: Some(container.to_string()),
+        tail,
+        follow,
+        ..Default::default()
+    })?;
+
+    Command::new(kubectl)
+        .args(global)
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("kubectl not found: {e}"))
+}
+
+/// Kills every kubectl started by `stream_pod_all_container_logs`.
+#[tauri::command]
+pub async fn stop_pod_log_streams(streams: State<'_, LogStreams>) -> Result<(), String> {
+    let children = std::mem::take(&mut *streams.0.lock().map_err(|e| e.to_string())?);
+    for mut child in children {
+        let _ = child.start_kill();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every piece `next_capped_line` yields for `input`, read `chunk` bytes at a time.
+    async fn pieces(input: &[u8], max: usize, chunk: usize) -> Vec<(String, bool)> {
+        let mut reader = BufReader::with_capacity(chunk, input);
+        let mut pending = Vec::new();
+        let mut out = Vec::new();
+        while let Some(piece) = next_capped_line(&mut reader, max, &mut pending).await.unwrap() {
+            out.push(piece);
+        }
+        out
+    }
+
+    fn piece(text: &str, continues: bool) -> (String, bool) {
+        (text.to_string(), continues)
+    }
+
+    #[tokio::test]
+    async fn a_line_of_exactly_max_bytes_is_not_split() {
+        assert_eq!(pieces(b"abcd\nef\n", 4, 64).await, [piece("abcd", false), piece("ef", false)]);
+        assert_eq!(pieces(b"abcd\r\n", 4, 64).await, [piece("abcd", false)]);
+    }
+
+    #[tokio::test]
+    async fn a_line_one_byte_over_max_is_split() {
+        assert_eq!(pieces(b"abcde\n", 4, 64).await, [piece("abcd", true), piece("e", false)]);
+        assert_eq!(pieces(b"abcde\n", 4, 2).await, [piece("abcd", true), piece("e", false)]);
+    }
+
+    #[tokio::test]
+    async fn a_character_straddling_the_cap_moves_to_the_next_piece() {
+        assert_eq!(
+            pieces("abcé\n".as_bytes(), 4, 64).await,
+            [piece("abc", true), piece("é", false)]
+        );
+    }
+
+    #[tokio::test]
+    async fn crlf_line_endings_are_stripped() {
+        assert_eq!(pieces(b"ab\r\ncd\r\n", 4, 64).await, [piece("ab", false), piece("cd", false)]);
+        // The \r and \n arriving in separate reads.
+        assert_eq!(pieces(b"ab\r\ncd\r\n", 4, 3).await, [piece("ab", false), piece("cd", false)]);
+    }
+
+    #[tokio::test]
+    async fn a_trailing_line_without_newline_is_returned_at_eof() {
+        assert_eq!(pieces(b"ab\ncd", 4, 64).await, [piece("ab", false), piece("cd", false)]);
+        assert!(pieces(b"", 4, 64).await.is_empty());
+    }
+}
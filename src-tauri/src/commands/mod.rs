@@ -4,3 +4,5 @@ pub mod kubectl;
 pub mod logs;
 pub mod proxy;
 pub mod ai;
+pub mod settings;
+pub mod diagnostics;
@@ -1,26 +1,175 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::future::Future;
 use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 
 use chrono::Utc;
-use k8s_openapi::api::core::v1::{Namespace as K8sNamespace, Pod};
-use kube::{api::{DeleteParams, ListParams}, Api, Client, Config};
-use tauri::{AppHandle, Emitter, State};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::{
+    Container, ContainerStatus, Event, Namespace as K8sNamespace, Node, PersistentVolumeClaim,
+    Pod, PodSpec, Secret, Service, Toleration,
+};
+use k8s_openapi::api::policy::v1::PodDisruptionBudget;
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, OwnerReference, Time};
+use futures::StreamExt;
+use kube::{
+    api::{
+        ApiResource, DeleteParams, DynamicObject, EvictParams, GroupVersionKind, ListParams,
+        LogParams, Patch, PatchParams, PostParams,
+    },
+    config::{KubeConfigOptions, Kubeconfig},
+    discovery::{ApiCapabilities, Scope},
+    runtime::{wait::await_condition, watcher, WatchStreamExt},
+    Api, Client, Config,
+};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Semaphore;
 
-use crate::models::k8s::PodSummary;
+use crate::commands::kubeconfig::{
+    context_auth_info, context_connection_details, get_active_context,
+};
+use crate::commands::kubectl::{
+    global_args, kubectl_args, kubectl_binary, list_api_resources, KubectlParams,
+};
+use crate::commands::settings::{ensure_writable, request_limiter};
+use crate::models::k8s::{
+    ApiResourceInfo, ClusterSnapshot, ContainerDetail, ContainerFailure, ContainerImage,
+    ContainerInfo, ContainerStatusSummary, ContainerTermination, DeletePodResult, DeletePreview,
+    EffectiveTarget, ExecOutput, FinishedPod, ImagePullDiagnosis, JobPodLogs, KindCount,
+    NamespaceInfo, NamespacePodCounts, OwnerRef, PodCondition, PodDetail, PodList, PodScheduling,
+    PodSummary, PodTemplateDrift, RestartVelocity, RolloutProgress, WarningSummary,
+};
+use crate::settings::{exec_shell_for_image, Settings, DEFAULT_EXEC_SHELL};
+use crate::{ApiResourcesCache, AppSettings, ClientMode, KubeClientMode, MetricsHintShown};
 
 // ── Client ────────────────────────────────────────────────────────────────────
 
-/// Builds a kube Client that talks to the kubectl proxy on :8001.
-/// kubectl proxy handles all auth (exec plugins, aws-iam-authenticator, kubelogin, etc.)
-/// so kube-rs never needs to run credential plugins itself.
-async fn build_client() -> Result<Client, String> {
-    let url: http::Uri = "http://127.0.0.1:8001"
-        .parse()
-        .map_err(|e| format!("proxy url: {e}"))?;
-    let config = Config::new(url);
+/// Builds a kube Client for the active client mode.
+///
+/// In proxy mode the client talks to the kubectl proxy on :8001, which handles
+/// all auth (exec plugins, aws-iam-authenticator, kubelogin, etc.) so kube-rs
+/// never needs to run credential plugins itself. In direct mode it connects
+/// straight to the API server using the selected kubeconfig context.
+async fn build_client(mode: &ClientMode) -> Result<Client, String> {
+    let mode = mode.0.lock().map_err(|e| e.to_string())?.clone();
+
+    match mode {
+        KubeClientMode::Proxy => {
+            let url: http::Uri = "http://127.0.0.1:8001"
+                .parse()
+                .map_err(|e| format!("proxy url: {e}"))?;
+            let config = Config::new(url);
+            Client::try_from(config).map_err(|e| format!("client error: {e}"))
+        }
+        KubeClientMode::Direct { source_file, context_name } => {
+            build_context_client(&source_file, &context_name).await
+        }
+        KubeClientMode::InCluster => {
+            let config = Config::incluster().map_err(|e| format!("in-cluster config: {e}"))?;
+            Client::try_from(config).map_err(|e| format!("client error: {e}"))
+        }
+    }
+}
+
+/// Service-account mount that every pod gets unless automounting is disabled.
+const SERVICE_ACCOUNT_DIR: &str = "/var/run/secrets/kubernetes.io/serviceaccount";
+
+/// True when this process runs inside a pod with a service account mounted.
+pub(crate) fn in_cluster_env() -> bool {
+    std::env::var_os("KUBERNETES_SERVICE_HOST").is_some()
+        && std::path::Path::new(SERVICE_ACCOUNT_DIR).is_dir()
+}
+
+/// Builds a kube Client straight from `source_file`, targeting `context_name`.
+/// Used by commands that name their cluster explicitly rather than relying on
+/// whichever context the proxy was started with.
+pub(crate) async fn build_context_client(source_file: &str, context_name: &str) -> Result<Client, String> {
+    let kubeconfig = Kubeconfig::read_from(source_file)
+        .map_err(|e| format!("Failed to read kubeconfig: {e}"))?;
+    let options = KubeConfigOptions {
+        context: Some(context_name.to_string()),
+        ..Default::default()
+    };
+    let config = Config::from_custom_kubeconfig(kubeconfig, &options)
+        .await
+        .map_err(|e| format!("kubeconfig error: {e}"))?;
     Client::try_from(config).map_err(|e| format!("client error: {e}"))
 }
 
+/// Server URL configured for `context_name` in `source_file`.
+fn context_server_url(source_file: &str, context_name: &str) -> Result<Option<String>, String> {
+    let kubeconfig = Kubeconfig::read_from(source_file)
+        .map_err(|e| format!("Failed to read kubeconfig: {e}"))?;
+    let ctx = kubeconfig
+        .contexts
+        .iter()
+        .find(|c| c.name == context_name)
+        .and_then(|c| c.context.as_ref())
+        .ok_or_else(|| format!("Context '{context_name}' not found in {source_file}"))?;
+    let server_url = kubeconfig
+        .clusters
+        .iter()
+        .find(|c| c.name == ctx.cluster)
+        .and_then(|c| c.cluster.as_ref())
+        .and_then(|c| c.server.clone());
+    Ok(server_url)
+}
+
+/// Returns true when the user bound to `context_name` authenticates through an
+/// exec credential plugin or legacy auth-provider — auth that only kubectl
+/// knows how to drive, so those contexts must stay on the proxy.
+fn context_needs_kubectl_auth(kubeconfig: &Kubeconfig, context_name: &str) -> Result<bool, String> {
+    Ok(context_auth_info(kubeconfig, context_name)?
+        .is_some_and(|info| info.exec.is_some() || info.auth_provider.is_some()))
+}
+
+// ── Namespace validation ──────────────────────────────────────────────────────
+
+/// Namespaces that can only be deleted with an explicit confirmation.
+const PROTECTED_NAMESPACES: &[&str] = &["default", "kube-system", "kube-public", "kube-node-lease"];
+
+/// Validates `name` against the RFC 1123 label rules Kubernetes applies to
+/// namespace names: 1–63 chars, lowercase alphanumerics or '-', and must
+/// start and end with an alphanumeric.
+fn validate_namespace_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 63 {
+        return Err(format!("Invalid namespace name '{name}': must be 1-63 characters"));
+    }
+    if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        return Err(format!(
+            "Invalid namespace name '{name}': only lowercase letters, digits and '-' are allowed"
+        ));
+    }
+    if name.starts_with('-') || name.ends_with('-') {
+        return Err(format!(
+            "Invalid namespace name '{name}': must start and end with a letter or digit"
+        ));
+    }
+    Ok(())
+}
+
+/// Validates a node name as an RFC 1123 subdomain: at most 253 chars of
+/// lowercase alphanumerics, '-' or '.', starting and ending alphanumeric.
+fn validate_node_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 253
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.')
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric());
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid node name '{name}'"))
+    }
+}
+
 // ── Status computation ────────────────────────────────────────────────────────
 
 fn compute_pod_status(pod: &Pod) -> String {
@@ -87,9 +236,138 @@ fn format_age(ts: &k8s_openapi::apimachinery::pkg::apis::meta::v1::Time) -> Stri
     }
 }
 
+fn invalid_duration(s: &str) -> String {
+    format!("Invalid duration '{s}' (expected e.g. 30m, 12h, 7d or 1h30m)")
+}
+
+/// Parses a duration like "90s", "30m", "12h", "7d" or "1h30m". Values too
+/// large for `chrono::Duration` are invalid rather than a panic.
+fn parse_duration(s: &str) -> Result<chrono::Duration, String> {
+    let invalid = || invalid_duration(s);
+    let mut total = chrono::Duration::zero();
+    let mut digits = String::new();
+    for c in s.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let n: i64 = digits.parse().map_err(|_| invalid())?;
+        digits.clear();
+        let part = match c {
+            's' => chrono::Duration::try_seconds(n),
+            'm' => chrono::Duration::try_minutes(n),
+            'h' => chrono::Duration::try_hours(n),
+            'd' => chrono::Duration::try_days(n),
+            _ => return Err(invalid()),
+        };
+        total = part.and_then(|part| total.checked_add(&part)).ok_or_else(invalid)?;
+    }
+    if !digits.is_empty() || total.is_zero() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+// ── Metrics ───────────────────────────────────────────────────────────────────
+
+/// Pod usage keyed by (namespace, name): (CPU millicores, memory bytes).
+type PodUsage = HashMap<(String, String), (u64, u64)>;
+
+/// Converts a CPU quantity ("250m", "1", "12345678n", "500u") to millicores.
+fn parse_cpu_millis(q: &str) -> u64 {
+    let (num, scale) = if let Some(n) = q.strip_suffix('n') {
+        (n, 1e-6)
+    } else if let Some(u) = q.strip_suffix('u') {
+        (u, 1e-3)
+    } else if let Some(m) = q.strip_suffix('m') {
+        (m, 1.0)
+    } else {
+        (q, 1000.0)
+    };
+    num.parse::<f64>().map(|v| (v * scale).round() as u64).unwrap_or(0)
+}
+
+/// Converts a memory quantity ("128974848", "123Mi", "1Gi", "500k") to bytes.
+fn parse_memory_bytes(q: &str) -> u64 {
+    const SUFFIXES: &[(&str, f64)] = &[
+        ("Ki", 1024.0),
+        ("Mi", 1024.0 * 1024.0),
+        ("Gi", 1024.0 * 1024.0 * 1024.0),
+        ("Ti", 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("k", 1e3),
+        ("M", 1e6),
+        ("G", 1e9),
+        ("T", 1e12),
+    ];
+    let (num, scale) = SUFFIXES
+        .iter()
+        .find_map(|(suffix, scale)| q.strip_suffix(suffix).map(|n| (n, *scale)))
+        .unwrap_or((q, 1.0));
+    num.parse::<f64>().map(|v| (v * scale).round() as u64).unwrap_or(0)
+}
+
+/// Fetches `metrics.k8s.io/v1beta1` PodMetrics and sums container usage per pod.
+///
+/// Returns None when metrics-server did not respond — a 404 (not installed)
+/// or a second failure after one retry — so callers keep "N/A" without failing
+/// the whole listing. Pods missing from a partial response (e.g. on a briefly
+/// NotReady node) are simply absent from the map.
+///
+/// On 404 a `metrics-unavailable` event is emitted, payload `null` — once per
+/// connection (see `MetricsHintShown`) rather than on every refresh.
+async fn fetch_pod_metrics(
+    app: &AppHandle,
+    client: Client,
+    namespace: Option<&str>,
+) -> Option<PodUsage> {
+    let gvk = GroupVersionKind::gvk("metrics.k8s.io", "v1beta1", "PodMetrics");
+    let resource = ApiResource::from_gvk_with_plural(&gvk, "pods");
+    let api: Api<DynamicObject> = match namespace {
+        Some(ns) => Api::namespaced_with(client, ns, &resource),
+        None => Api::all_with(client, &resource),
+    };
+
+    let mut attempt = 0;
+    let list = loop {
+        match api.list(&ListParams::default()).await {
+            Ok(list) => break list,
+            Err(kube::Error::Api(e)) if e.code == 404 => {
+                if !app.state::<MetricsHintShown>().0.swap(true, Ordering::Relaxed) {
+                    let _ = app.emit("metrics-unavailable", ());
+                }
+                return None;
+            }
+            Err(_) if attempt == 0 => {
+                attempt += 1;
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            }
+            Err(_) => return None,
+        }
+    };
+
+    Some(
+        list.items
+            .into_iter()
+            .filter_map(|m| {
+                let name = m.metadata.name?;
+                let namespace = m.metadata.namespace.unwrap_or_default();
+                let (mut cpu, mut memory) = (0, 0);
+                for c in m.data["containers"].as_array().into_iter().flatten() {
+                    cpu += c["usage"]["cpu"].as_str().map(parse_cpu_millis).unwrap_or(0);
+                    memory += c["usage"]["memory"].as_str().map(parse_memory_bytes).unwrap_or(0);
+                }
+                Some(((namespace, name), (cpu, memory)))
+            })
+            .collect(),
+    )
+}
+
 // ── Pod → PodSummary ──────────────────────────────────────────────────────────
 
-fn pod_to_summary(pod: Pod) -> PodSummary {
+/// Builds the list-view summary. Only labels whose key starts with one of
+/// `label_prefixes` are kept, so pods with dozens of large labels don't bloat
+/// `list_pods` on big clusters; `get_pod` returns them all.
+fn pod_to_summary(pod: Pod, label_prefixes: &[String]) -> PodSummary {
     let meta = &pod.metadata;
     let spec = pod.spec.as_ref();
     let status = pod.status.as_ref();
@@ -117,6 +395,7 @@ fn pod_to_summary(pod: Pod) -> PodSummary {
         .as_ref()
         .map(format_age)
         .unwrap_or_else(|| "unknown".to_string());
+    let created_at = meta.creation_timestamp.as_ref().map(|ts| ts.0.to_rfc3339());
 
     // Node
     let node = spec
@@ -126,195 +405,3001 @@ fn pod_to_summary(pod: Pod) -> PodSummary {
     // Labels — BTreeMap → HashMap for JSON serialisation
     let labels: HashMap<String, String> = meta
         .labels
-        .clone()
+        .iter()
+        .flatten()
+        .filter(|(k, _)| label_prefixes.iter().any(|p| k.starts_with(p.as_str())))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    // Per-container health, in spec order; containers without a status yet are "unknown"
+    let statuses = status.and_then(|s| s.container_statuses.as_ref());
+    let containers = spec
+        .map(|s| s.containers.as_slice())
         .unwrap_or_default()
-        .into_iter()
+        .iter()
+        .map(|c| {
+            let cs = statuses.and_then(|css| css.iter().find(|cs| cs.name == c.name));
+            let (state, reason) = container_state(cs);
+            ContainerStatusSummary {
+                name: c.name.clone(),
+                ready: cs.is_some_and(|cs| cs.ready),
+                restarts: cs.map(|cs| cs.restart_count.max(0) as u32).unwrap_or(0),
+                state: state.to_string(),
+                reason,
+            }
+        })
         .collect();
 
     PodSummary {
         status: compute_pod_status(&pod),
+        uid: meta.uid.clone().unwrap_or_default(),
         name,
         namespace,
         ready,
         restarts,
         age,
-        cpu: "N/A".to_string(),    // metrics-server — Phase 2
-        memory: "N/A".to_string(), // metrics-server — Phase 2
+        created_at,
+        cpu: "N/A".to_string(),    // filled from metrics-server in list_pods
+        memory: "N/A".to_string(), // filled from metrics-server in list_pods
         node,
         labels,
+        containers,
     }
 }
 
-// ── Commands ──────────────────────────────────────────────────────────────────
-
-/// Lists pods in `namespace`, or all namespaces when `namespace` is None / empty.
-#[tauri::command]
-pub async fn list_pods(namespace: Option<String>) -> Result<Vec<PodSummary>, String> {
-    let client = build_client().await?;
+// ── Pod → PodDetail ───────────────────────────────────────────────────────────
 
-    let pods = match namespace.as_deref().filter(|s| !s.is_empty()) {
-        Some(ns) => {
-            let api: Api<Pod> = Api::namespaced(client, ns);
-            api.list(&ListParams::default())
-                .await
-                .map_err(|e| e.to_string())?
-                .items
+/// "running" / "waiting" / "terminated" / "unknown" plus the waiting or
+/// terminated reason, from a container status entry.
+fn container_state(cs: Option<&ContainerStatus>) -> (&'static str, Option<String>) {
+    match cs.and_then(|cs| cs.state.as_ref()) {
+        Some(s) if s.running.is_some() => ("running", None),
+        Some(s) if s.waiting.is_some() => {
+            ("waiting", s.waiting.as_ref().and_then(|w| w.reason.clone()))
         }
-        None => {
-            let api: Api<Pod> = Api::all(client);
-            api.list(&ListParams::default())
-                .await
-                .map_err(|e| e.to_string())?
-                .items
+        Some(s) if s.terminated.is_some() => {
+            ("terminated", s.terminated.as_ref().and_then(|t| t.reason.clone()))
         }
-    };
+        _ => ("unknown", None),
+    }
+}
+
+/// Pairs a container spec with its status entry (matched by name).
+fn container_detail(c: &Container, statuses: Option<&Vec<ContainerStatus>>) -> ContainerDetail {
+    let cs = statuses.and_then(|css| css.iter().find(|cs| cs.name == c.name));
+    let (state_name, reason) = container_state(cs);
 
-    Ok(pods.into_iter().map(pod_to_summary).collect())
+    ContainerDetail {
+        name: c.name.clone(),
+        image: c.image.clone().unwrap_or_default(),
+        state: state_name.to_string(),
+        reason,
+        ready: cs.is_some_and(|cs| cs.ready),
+        restart_count: cs.map(|cs| cs.restart_count.max(0) as u32).unwrap_or(0),
+        last_termination_reason: cs
+            .and_then(|cs| cs.last_state.as_ref())
+            .and_then(|ls| ls.terminated.as_ref())
+            .and_then(|t| t.reason.clone()),
+    }
 }
 
-/// Lists all namespace names in the active cluster.
-#[tauri::command]
-pub async fn list_namespaces() -> Result<Vec<String>, String> {
-    let client = build_client().await?;
-    let api: Api<K8sNamespace> = Api::all(client);
+fn pod_to_detail(pod: Pod) -> PodDetail {
+    let status_str = compute_pod_status(&pod);
+    let meta = pod.metadata;
+    let spec = pod.spec.unwrap_or_default();
+    let status = pod.status.unwrap_or_default();
 
-    let ns_list = api
-        .list(&ListParams::default())
-        .await
-        .map_err(|e| e.to_string())?;
+    let containers = spec
+        .containers
+        .iter()
+        .map(|c| container_detail(c, status.container_statuses.as_ref()))
+        .collect();
+    let init_containers = spec
+        .init_containers
+        .iter()
+        .flatten()
+        .map(|c| container_detail(c, status.init_container_statuses.as_ref()))
+        .collect();
 
-    let mut names: Vec<String> = ns_list
-        .items
+    let conditions = status
+        .conditions
+        .unwrap_or_default()
         .into_iter()
-        .filter_map(|ns| ns.metadata.name)
+        .map(|c| PodCondition {
+            condition_type: c.type_,
+            status: c.status,
+            reason: c.reason,
+            message: c.message,
+        })
+        .collect();
+
+    let owner_references = meta
+        .owner_references
+        .unwrap_or_default()
+        .into_iter()
+        .map(|o| OwnerRef { kind: o.kind, name: o.name })
         .collect();
 
-    names.sort();
-    Ok(names)
+    PodDetail {
+        name: meta.name.unwrap_or_default(),
+        namespace: meta.namespace.unwrap_or_default(),
+        uid: meta.uid.unwrap_or_default(),
+        status: status_str,
+        node: spec.node_name.unwrap_or_default(),
+        pod_ip: status.pod_ip,
+        start_time: status.start_time.map(|t| t.0.to_rfc3339()),
+        qos_class: status.qos_class,
+        containers,
+        init_containers,
+        conditions,
+        owner_references,
+        labels: meta.labels.unwrap_or_default().into_iter().collect(),
+    }
 }
 
-/// Deletes a pod by name and namespace using kube-rs.
-#[tauri::command]
-pub async fn delete_pod(name: String, namespace: String) -> Result<(), String> {
-    let client = build_client().await?;
-    let api: Api<Pod> = Api::namespaced(client, &namespace);
-    api.delete(&name, &DeleteParams::default())
-        .await
-        .map_err(|e| e.to_string())?;
-    Ok(())
+// ── Scheduling ────────────────────────────────────────────────────────────────
+
+/// Derives the QoS class from container resources the way the kubelet does:
+/// no cpu/memory requests or limits anywhere → BestEffort; every container
+/// with cpu and memory limits equal to its requests → Guaranteed (a missing
+/// request defaults to the limit); anything else → Burstable.
+fn derive_qos_class(spec: &PodSpec) -> &'static str {
+    let mut any_set = false;
+    let mut guaranteed = true;
+
+    for c in spec.containers.iter().chain(spec.init_containers.iter().flatten()) {
+        let resources = c.resources.as_ref();
+        let requests = resources.and_then(|r| r.requests.as_ref());
+        let limits = resources.and_then(|r| r.limits.as_ref());
+
+        for resource in ["cpu", "memory"] {
+            let parse = |q: &Quantity| match resource {
+                "cpu" => parse_cpu_millis(&q.0),
+                _ => parse_memory_bytes(&q.0),
+            };
+            let request = requests.and_then(|m| m.get(resource)).map(parse).filter(|v| *v > 0);
+            let limit = limits.and_then(|m| m.get(resource)).map(parse).filter(|v| *v > 0);
+
+            if request.is_some() || limit.is_some() {
+                any_set = true;
+            }
+            match (request, limit) {
+                (_, None) => guaranteed = false,
+                (Some(r), Some(l)) if r != l => guaranteed = false,
+                _ => {}
+            }
+        }
+    }
+
+    if !any_set {
+        "BestEffort"
+    } else if guaranteed {
+        "Guaranteed"
+    } else {
+        "Burstable"
+    }
 }
 
-/// Opens a PTY, spawns `kubectl exec -it` inside it, and streams raw PTY output
-/// to the frontend via `exec-output` events.  The frontend's xterm.js terminal
-/// writes bytes directly to the PTY master via the `send_exec_input` command,
-/// giving a fully interactive shell session inside the app.
+/// Formats a toleration like `kubectl describe`, e.g.
+/// `node.kubernetes.io/not-ready:NoExecute op=Exists for 300s`.
+fn format_toleration(t: &Toleration) -> String {
+    let mut out = t.key.clone().unwrap_or_default();
+    if let Some(value) = t.value.as_deref().filter(|v| !v.is_empty()) {
+        out.push_str(&format!("={value}"));
+    }
+    if let Some(effect) = t.effect.as_deref().filter(|e| !e.is_empty()) {
+        out.push_str(&format!(":{effect}"));
+    }
+    if t.operator.as_deref() == Some("Exists") {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str("op=Exists");
+    }
+    if let Some(secs) = t.toleration_seconds {
+        out.push_str(&format!(" for {secs}s"));
+    }
+    out
+}
+
+// ── Ownership & disruption budgets ────────────────────────────────────────────
+
+/// Owner kinds whose controllers replace a deleted pod.
+const RECREATING_OWNERS: &[&str] = &[
+    "ReplicaSet",
+    "ReplicationController",
+    "StatefulSet",
+    "DaemonSet",
+    "Job",
+];
+
+/// Picks the managing controller from `ownerReferences`, falling back to the
+/// first owner when none is flagged `controller: true`.
+fn controller_ref(refs: Option<&Vec<OwnerReference>>) -> Option<&OwnerReference> {
+    let refs = refs?;
+    refs.iter()
+        .find(|o| o.controller == Some(true))
+        .or_else(|| refs.first())
+}
+
+/// Evaluates a LabelSelector (matchLabels + matchExpressions) against `labels`.
+/// An empty selector matches everything, as in the Kubernetes API.
+fn selector_matches(selector: &LabelSelector, labels: &BTreeMap<String, String>) -> bool {
+    let labels_ok = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .all(|(k, v)| labels.get(k) == Some(v));
+
+    let exprs_ok = selector.match_expressions.iter().flatten().all(|e| {
+        let values = e.values.as_deref().unwrap_or_default();
+        match e.operator.as_str() {
+            "In" => labels.get(&e.key).is_some_and(|v| values.contains(v)),
+            "NotIn" => !labels.get(&e.key).is_some_and(|v| values.contains(v)),
+            "Exists" => labels.contains_key(&e.key),
+            "DoesNotExist" => !labels.contains_key(&e.key),
+            _ => false,
+        }
+    });
+
+    labels_ok && exprs_ok
+}
+
+// ── Commands ──────────────────────────────────────────────────────────────────
+
+/// Switches list/delete commands (`list_pods`, `list_namespaces`, …) to talk
+/// to the API server directly using `source_file` / `context_name`, skipping
+/// kubectl proxy. Bearer-token, client-certificate and basic-auth contexts
+/// connect this way, with the cluster's CA and `insecure-skip-tls-verify`
+/// honoured by kube-rs.
 ///
-/// Events emitted:
-///   `exec-output` — payload: String  — raw PTY bytes (ANSI sequences included)
-///   `exec-done`   — payload: null    — session ended
+/// Contexts that authenticate via an exec credential plugin or legacy
+/// auth-provider stay on the proxy; the frontend only starts kubectl proxy
+/// when this returns `"proxy"`.
+/// Returns the mode now in effect: `"direct"` or `"proxy"`.
 #[tauri::command]
-pub async fn exec_into_pod(
-    app: AppHandle,
-    name: String,
-    namespace: String,
+pub async fn use_direct_client(
     source_file: String,
     context_name: String,
-    state: State<'_, crate::PtyState>,
+    mode: State<'_, ClientMode>,
+    metrics_hint: State<'_, MetricsHintShown>,
+) -> Result<String, String> {
+    let kubeconfig = Kubeconfig::read_from(&source_file)
+        .map_err(|e| format!("Failed to read kubeconfig: {e}"))?;
+
+    let next = if context_needs_kubectl_auth(&kubeconfig, &context_name)? {
+        KubeClientMode::Proxy
+    } else {
+        // Fail early if kube-rs can't make sense of the context.
+        build_context_client(&source_file, &context_name).await?;
+        KubeClientMode::Direct { source_file, context_name }
+    };
+
+    let label = match next {
+        KubeClientMode::Direct { .. } => "direct",
+        _ => "proxy",
+    };
+    *mode.0.lock().map_err(|e| e.to_string())? = next;
+    metrics_hint.0.store(false, Ordering::Relaxed);
+    Ok(label.to_string())
+}
+
+/// Switches list/delete commands to the in-cluster API using the pod's
+/// service account, bypassing kubectl proxy and kubeconfig entirely.
+#[tauri::command]
+pub async fn connect_in_cluster(
+    mode: State<'_, ClientMode>,
+    metrics_hint: State<'_, MetricsHintShown>,
 ) -> Result<(), String> {
-    // Clear previous PTY
-    {
-        let mut guard = state.0.lock().map_err(|e| e.to_string())?;
-        *guard = None;
+    if !in_cluster_env() {
+        return Err("Not running inside a Kubernetes pod".to_string());
     }
+    Config::incluster().map_err(|e| format!("in-cluster config: {e}"))?;
+    *mode.0.lock().map_err(|e| e.to_string())? = KubeClientMode::InCluster;
+    metrics_hint.0.store(false, Ordering::Relaxed);
+    Ok(())
+}
 
-    let name2 = name.clone();
-    let namespace2 = namespace.clone();
-    let source_file2 = source_file.clone();
-    let context_name2 = context_name.clone();
-    let kubectl = which::which("kubectl")
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| "kubectl".to_string());
+/// Switches list/delete commands back to going through kubectl proxy.
+#[tauri::command]
+pub async fn use_proxy_client(
+    mode: State<'_, ClientMode>,
+    metrics_hint: State<'_, MetricsHintShown>,
+) -> Result<(), String> {
+    *mode.0.lock().map_err(|e| e.to_string())? = KubeClientMode::Proxy;
+    metrics_hint.0.store(false, Ordering::Relaxed);
+    Ok(())
+}
 
-    let (writer, reader, child, slave) = tokio::task::spawn_blocking(move || {
-        let pty_system = portable_pty::native_pty_system();
-        let pty_pair = pty_system.openpty(portable_pty::PtySize {
-            rows: 24, cols: 80,
-            pixel_width: 0, pixel_height: 0,
-        }).map_err(|e| e.to_string())?;
+/// Saves the namespace used when commands are called without one, or clears
+/// it when `namespace` is None. Persisted across restarts.
+#[tauri::command]
+pub async fn set_default_namespace(
+    app: AppHandle,
+    namespace: Option<String>,
+    settings: State<'_, AppSettings>,
+) -> Result<(), String> {
+    let mut guard = settings.0.lock().map_err(|e| e.to_string())?;
+    guard.default_namespace = namespace.filter(|ns| !ns.is_empty());
+    guard.save(&app)
+}
 
-        let mut cmd = portable_pty::CommandBuilder::new(&kubectl);
-        cmd.args([
-            "exec", "-it", &name2, "-n", &namespace2,
-            &format!("--kubeconfig={source_file2}"),
-            &format!("--context={context_name2}"),
-            "--", "/bin/sh",
-        ]);
-        cmd.env("TERM", "xterm-256color");
+#[tauri::command]
+pub async fn get_default_namespace(
+    settings: State<'_, AppSettings>,
+) -> Result<Option<String>, String> {
+    Ok(settings.0.lock().map_err(|e| e.to_string())?.default_namespace.clone())
+}
 
-        let child = pty_pair.slave.spawn_command(cmd)
-            .map_err(|e| format!("spawn failed: {e}"))?;
+/// What a command without explicit target arguments will hit, for the UI
+/// header. The context follows the client mode: the Direct context, the
+/// service account for in-cluster, else the kubeconfig's current context
+/// (which `set_active_context` keeps in step with the selected cluster).
+/// The namespace is the one `list_pods` uses (see `command_namespace`);
+/// None when no default namespace is set and `list_pods` would refuse.
+#[tauri::command]
+pub async fn effective_target(
+    mode: State<'_, ClientMode>,
+    settings: State<'_, AppSettings>,
+) -> Result<EffectiveTarget, String> {
+    let mode = mode.0.lock().map_err(|e| e.to_string())?.clone();
+    let settings = settings.0.lock().map_err(|e| e.to_string())?.clone();
+    let namespace = command_namespace(&settings, None).ok();
 
-        let reader = pty_pair.master.try_clone_reader()
-            .map_err(|e| e.to_string())?;
-        let writer = pty_pair.master.take_writer()
-            .map_err(|e| e.to_string())?;
+    let (context, source_file, server_url) = match mode {
+        KubeClientMode::InCluster => {
+            let server_url = std::env::var("KUBERNETES_SERVICE_HOST").ok().map(|host| {
+                let port = std::env::var("KUBERNETES_SERVICE_PORT").unwrap_or("443".to_string());
+                format!("https://{host}:{port}")
+            });
+            ("in-cluster".to_string(), String::new(), server_url)
+        }
+        KubeClientMode::Direct { source_file, context_name } => {
+            let server_url = context_server_url(&source_file, &context_name)?;
+            (context_name, source_file, server_url)
+        }
+        KubeClientMode::Proxy => {
+            let active = get_active_context()
+                .await?
+                .ok_or_else(|| "No current context in the kubeconfig".to_string())?;
+            let server_url = context_server_url(&active.source_file, &active.context_name)?;
+            (active.context_name, active.source_file, server_url)
+        }
+    };
 
-        // Move slave out so it can be kept alive in the reader closure.
-        let slave = pty_pair.slave;
+    Ok(EffectiveTarget { context, namespace, source_file, server_url })
+}
 
-        Ok::<_, String>((writer, reader, child, slave))
-    }).await.map_err(|e| e.to_string())??;
+/// The namespace a single-namespace command like `list_pods` targets: a
+/// non-empty `explicit` one, else the default namespace setting. Errors when
+/// neither is set.
+fn command_namespace(settings: &Settings, explicit: Option<String>) -> Result<String, String> {
+    settings.namespace_or_default(explicit).ok_or_else(|| {
+        "No namespace given and no default namespace set; use list_all_pods for every namespace"
+            .to_string()
+    })
+}
 
-    // Store only the writer in state so send_exec_input can forward keystrokes.
-    {
-        let mut guard = state.0.lock().map_err(|e| e.to_string())?;
-        *guard = Some(writer);
+/// Lists pods in one namespace: a non-empty `namespace`, else the default
+/// namespace from `set_default_namespace`. Errors when neither is set — use
+/// `list_all_pods` for the cluster-wide view. `all_namespaces: true` still
+/// lists every namespace while callers move to `list_all_pods`.
+///
+/// CPU / memory come from metrics-server when available, otherwise "N/A";
+/// `metrics_available` says whether metrics-server responded at all.
+#[tauri::command]
+pub async fn list_pods(
+    app: AppHandle,
+    namespace: Option<String>,
+    all_namespaces: Option<bool>,
+    mode: State<'_, ClientMode>,
+    settings: State<'_, AppSettings>,
+) -> Result<PodList, String> {
+    if all_namespaces.unwrap_or(false) {
+        return list_all_pods(app, None, None, mode, settings).await;
     }
 
-    // Read PTY output in background.
-    // child and slave are moved into this closure so they stay alive for the
-    // entire session — dropping either would kill the process or close the PTY.
-    let app_clone = app.clone();
-    tokio::task::spawn_blocking(move || {
-        let _child = child;   // keeps kubectl exec process alive
-        let _slave = slave;   // keeps PTY slave fd open
-        let mut reader = reader;
-        let mut buf = [0u8; 1024];
-        loop {
-            match reader.read(&mut buf) {
-                Ok(0) => break,
-                Ok(n) => {
-                    let data = String::from_utf8_lossy(&buf[..n]).to_string();
-                    if app_clone.emit("exec-output", data).is_err() {
-                        break;
-                    }
-                }
-                Err(_) => {
-                    break;
-                }
-            }
-        }
-        let _ = app_clone.emit("exec-done", ());
-    });
+    let (namespace, label_prefixes) = {
+        let settings = settings.0.lock().map_err(|e| e.to_string())?;
+        (command_namespace(&settings, namespace)?, settings.summary_label_prefixes())
+    };
 
-    Ok(())
+    let client = build_client(&mode).await?;
+    let params = ListParams::default();
+    list_pod_summaries(&app, client, Some(&namespace), &params, None, &label_prefixes).await
 }
 
-/// Forwards raw keystroke bytes from xterm.js to the PTY master writer.
-/// Called on every xterm.js `onData` event.
+/// Sort keys accepted by `list_all_pods`.
+const POD_SORT_KEYS: &[&str] = &["name", "namespace", "age", "restarts", "status"];
+
+/// Lists pods across every namespace, optionally filtered by a label selector
+/// (e.g. `app=web,tier!=cache`) and sorted by one of `POD_SORT_KEYS`: "age"
+/// is newest first, "restarts" most first, the rest alphabetical. Unsorted
+/// results keep the API server's order (namespace, then name).
 #[tauri::command]
-pub async fn send_exec_input(
-    input: String,
-    state: State<'_, crate::PtyState>,
-) -> Result<(), String> {
-    let mut guard = state.0.lock().map_err(|e| e.to_string())?;
-    if let Some(ref mut writer) = *guard {
-        writer
-            .write_all(input.as_bytes())
-            .map_err(|e| format!("PTY write error: {e}"))?;
-        writer
-            .flush()
-            .map_err(|e| format!("PTY flush error: {e}"))?;
+pub async fn list_all_pods(
+    app: AppHandle,
+    sort_by: Option<String>,
+    label_selector: Option<String>,
+    mode: State<'_, ClientMode>,
+    settings: State<'_, AppSettings>,
+) -> Result<PodList, String> {
+    let sort_by = sort_by.filter(|s| !s.is_empty());
+    if let Some(key) = sort_by.as_deref().filter(|k| !POD_SORT_KEYS.contains(k)) {
+        return Err(format!(
+            "Unsupported sort key '{key}' (expected one of: {})",
+            POD_SORT_KEYS.join(", ")
+        ));
+    }
+    let label_prefixes = settings.0.lock().map_err(|e| e.to_string())?.summary_label_prefixes();
+
+    let mut params = ListParams::default();
+    if let Some(selector) = label_selector.as_deref().filter(|s| !s.is_empty()) {
+        params = params.labels(selector);
+    }
+
+    let client = build_client(&mode).await?;
+    list_pod_summaries(&app, client, None, &params, sort_by.as_deref(), &label_prefixes).await
+}
+
+/// Like `list_pods`, but against `context_name` in `source_file` through a
+/// one-off direct client, so another cluster can be inspected without
+/// restarting the proxy or changing the client mode. The client is dropped
+/// when the command returns. `namespace` falls back to the default namespace
+/// setting, then to all namespaces.
+#[tauri::command]
+pub async fn list_pods_for_context(
+    app: AppHandle,
+    namespace: Option<String>,
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+) -> Result<PodList, String> {
+    let (namespace, label_prefixes) = {
+        let settings = settings.0.lock().map_err(|e| e.to_string())?;
+        (settings.namespace_or_default(namespace), settings.summary_label_prefixes())
+    };
+
+    let client = build_context_client(&source_file, &context_name).await?;
+    let params = ListParams::default();
+    list_pod_summaries(&app, client, namespace.as_deref(), &params, None, &label_prefixes).await
+}
+
+/// Sorts raw pods by one of `POD_SORT_KEYS`; None or an unknown key leaves
+/// the order alone.
+fn sort_pods(pods: &mut [Pod], sort_by: Option<&str>) {
+    let restarts = |pod: &Pod| -> i32 {
+        pod.status
+            .as_ref()
+            .and_then(|s| s.container_statuses.as_ref())
+            .map(|css| css.iter().map(|cs| cs.restart_count.max(0)).sum())
+            .unwrap_or(0)
+    };
+    match sort_by {
+        Some("name") => pods.sort_by(|a, b| a.metadata.name.cmp(&b.metadata.name)),
+        Some("namespace") => pods.sort_by(|a, b| {
+            let key = |p: &Pod| (p.metadata.namespace.clone(), p.metadata.name.clone());
+            key(a).cmp(&key(b))
+        }),
+        Some("age") => pods.sort_by(|a, b| {
+            let created = |p: &Pod| p.metadata.creation_timestamp.as_ref().map(|t| t.0);
+            created(b).cmp(&created(a))
+        }),
+        Some("restarts") => pods.sort_by_key(|p| std::cmp::Reverse(restarts(p))),
+        Some("status") => pods.sort_by_cached_key(compute_pod_status),
+        _ => {}
+    }
+}
+
+/// Lists pods in `namespace` (all when None) with metrics merged in,
+/// ordered by `sort_by` (see `POD_SORT_KEYS`) when given.
+async fn list_pod_summaries(
+    app: &AppHandle,
+    client: Client,
+    namespace: Option<&str>,
+    params: &ListParams,
+    sort_by: Option<&str>,
+    label_prefixes: &[String],
+) -> Result<PodList, String> {
+    let api: Api<Pod> = match namespace {
+        Some(ns) => Api::namespaced(client.clone(), ns),
+        None => Api::all(client.clone()),
+    };
+
+    let pods = async { api.list(params).await.map(|l| l.items).map_err(|e| e.to_string()) };
+    join_pods_with_metrics(pods, fetch_pod_metrics(app, client, namespace), sort_by, label_prefixes)
+        .await
+}
+
+/// Awaits the pod listing and the metrics fetch together, so metrics don't add
+/// to the wall-clock time, and merges usage into the summaries. Only a listing
+/// error fails; missing metrics (None) leave "N/A" with `metrics_available`
+/// false.
+async fn join_pods_with_metrics(
+    pods: impl Future<Output = Result<Vec<Pod>, String>>,
+    usage: impl Future<Output = Option<PodUsage>>,
+    sort_by: Option<&str>,
+    label_prefixes: &[String],
+) -> Result<PodList, String> {
+    let (pods, usage) = tokio::join!(pods, usage);
+    let mut pods = pods?;
+    sort_pods(&mut pods, sort_by);
+
+    let pods = pods
+        .into_iter()
+        .map(|pod| {
+            let mut summary = pod_to_summary(pod, label_prefixes);
+            let key = (summary.namespace.clone(), summary.name.clone());
+            if let Some((cpu, memory)) = usage.as_ref().and_then(|u| u.get(&key)) {
+                summary.cpu = format!("{cpu}m");
+                summary.memory = format!("{}Mi", memory / (1024 * 1024));
+            }
+            summary
+        })
+        .collect();
+
+    Ok(PodList {
+        pods,
+        metrics_available: usage.is_some(),
+    })
+}
+
+/// Pods scheduled on `node_name`, across all namespaces — what a drain would
+/// move. Filtered server-side with a `spec.nodeName` field selector.
+#[tauri::command]
+pub async fn list_pods_on_node(
+    node_name: String,
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<PodSummary>, String> {
+    validate_node_name(&node_name)?;
+    let label_prefixes = settings.0.lock().map_err(|e| e.to_string())?.summary_label_prefixes();
+
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<Pod> = Api::all(client);
+    let params = ListParams::default().fields(&format!("spec.nodeName={node_name}"));
+    let pods = api.list(&params).await.map_err(|e| e.to_string())?;
+
+    Ok(pods
+        .items
+        .into_iter()
+        .map(|pod| pod_to_summary(pod, &label_prefixes))
+        .collect())
+}
+
+/// Statuses that count as healthy when ranking restarting pods.
+const HEALTHY_STATUSES: &[&str] = &["Running", "Succeeded", "Completed"];
+
+/// Returns the `limit` worst pods cluster-wide by restart count.
+///
+/// Ties go to pods in an unhealthy status (e.g. CrashLoopBackOff), and healthy
+/// pods with zero restarts are left out entirely. Sorting happens here so the
+/// UI never receives the full pod list.
+#[tauri::command]
+pub async fn top_restarting_pods(
+    limit: u32,
+    mode: State<'_, ClientMode>,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<PodSummary>, String> {
+    let label_prefixes = settings.0.lock().map_err(|e| e.to_string())?.summary_label_prefixes();
+    let client = build_client(&mode).await?;
+    let api: Api<Pod> = Api::all(client);
+
+    let pods = api
+        .list(&ListParams::default())
+        .await
+        .map_err(|e| e.to_string())?
+        .items;
+
+    let mut ranked: Vec<(bool, PodSummary)> = pods
+        .into_iter()
+        .map(|pod| pod_to_summary(pod, &label_prefixes))
+        .map(|s| (!HEALTHY_STATUSES.contains(&s.status.as_str()), s))
+        .filter(|(unhealthy, s)| *unhealthy || s.restarts > 0)
+        .collect();
+
+    ranked.sort_by(|(a_bad, a), (b_bad, b)| {
+        b.restarts
+            .cmp(&a.restarts)
+            .then(b_bad.cmp(a_bad))
+            .then_with(|| a.namespace.cmp(&b.namespace))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    ranked.truncate(limit as usize);
+
+    Ok(ranked.into_iter().map(|(_, s)| s).collect())
+}
+
+fn namespace_info(ns: K8sNamespace) -> NamespaceInfo {
+    NamespaceInfo {
+        name: ns.metadata.name.unwrap_or_default(),
+        phase: ns
+            .status
+            .and_then(|s| s.phase)
+            .unwrap_or_else(|| "Active".to_string()),
+    }
+}
+
+/// Fetches namespaces sorted by name, dropping ones that match the system
+/// prefixes from settings unless `include_system` (default true).
+async fn fetch_namespaces(
+    include_system: Option<bool>,
+    mode: &ClientMode,
+    settings: &AppSettings,
+) -> Result<Vec<K8sNamespace>, String> {
+    let hidden = if include_system.unwrap_or(true) {
+        Vec::new()
+    } else {
+        settings.0.lock().map_err(|e| e.to_string())?.system_prefixes()
+    };
+
+    let client = build_client(mode).await?;
+    let api: Api<K8sNamespace> = Api::all(client);
+
+    let ns_list = api
+        .list(&ListParams::default())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut namespaces: Vec<K8sNamespace> = ns_list
+        .items
+        .into_iter()
+        .filter(|ns| {
+            let name = ns.metadata.name.as_deref().unwrap_or_default();
+            !name.is_empty() && !hidden.iter().any(|p| name.starts_with(p.as_str()))
+        })
+        .collect();
+
+    namespaces.sort_by(|a, b| a.metadata.name.cmp(&b.metadata.name));
+    Ok(namespaces)
+}
+
+/// When an event last happened: `lastTimestamp`, else `eventTime`, else
+/// `firstTimestamp`, else when the object was created.
+fn event_time(event: &Event) -> Option<chrono::DateTime<Utc>> {
+    event
+        .last_timestamp
+        .as_ref()
+        .map(|t| t.0)
+        .or_else(|| event.event_time.as_ref().map(|t| t.0))
+        .or_else(|| event.first_timestamp.as_ref().map(|t| t.0))
+        .or_else(|| event.metadata.creation_timestamp.as_ref().map(|t| t.0))
+}
+
+/// Warning events across all namespaces, grouped by reason (FailedScheduling,
+/// BackOff, Unhealthy, …) with occurrence counts. Groups are ordered by their
+/// most recent event and cut to `limit`. `since` (RFC3339) drops older events;
+/// the API can't filter on time, so that happens here.
+#[tauri::command]
+pub async fn recent_warning_events(
+    since: Option<String>,
+    limit: u32,
+    source_file: String,
+    context_name: String,
+) -> Result<Vec<WarningSummary>, String> {
+    let since = since
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            chrono::DateTime::parse_from_rfc3339(&s)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|e| format!("Invalid since '{s}': {e}"))
+        })
+        .transpose()?;
+
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<Event> = Api::all(client);
+    let events = api
+        .list(&ListParams::default().fields("type=Warning"))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut groups: HashMap<String, (WarningSummary, chrono::DateTime<Utc>)> = HashMap::new();
+    for event in &events.items {
+        let Some(at) = event_time(event) else { continue };
+        if since.is_some_and(|since| at < since) {
+            continue;
+        }
+        let reason = event.reason.clone().unwrap_or_else(|| "Unknown".to_string());
+        let occurrences = event.count.unwrap_or(1).max(1) as u32;
+        let object = event.involved_object.name.as_deref().map(|name| {
+            let kind = event.involved_object.kind.as_deref().unwrap_or_default();
+            let ns = event.involved_object.namespace.as_deref().unwrap_or_default();
+            format!("{kind} {ns}/{name}")
+        });
+
+        let (summary, latest) = groups.entry(reason.clone()).or_insert_with(|| {
+            (
+                WarningSummary {
+                    reason,
+                    count: 0,
+                    last_seen: String::new(),
+                    last_message: None,
+                    last_object: None,
+                },
+                at,
+            )
+        });
+        summary.count += occurrences;
+        if at >= *latest {
+            *latest = at;
+            summary.last_seen = at.to_rfc3339();
+            summary.last_message = event.message.clone();
+            summary.last_object = object;
+        }
+    }
+
+    let mut groups: Vec<_> = groups.into_values().collect();
+    groups.sort_by_key(|(_, latest)| std::cmp::Reverse(*latest));
+    Ok(groups
+        .into_iter()
+        .take(limit as usize)
+        .map(|(summary, _)| summary)
+        .collect())
+}
+
+/// Lists all namespace names in the active cluster.
+///
+/// `include_system: false` hides `kube-*` / `openshift-*` namespaces (prefixes
+/// are configurable via `set_system_namespace_prefixes`).
+#[tauri::command]
+pub async fn list_namespaces(
+    include_system: Option<bool>,
+    mode: State<'_, ClientMode>,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<String>, String> {
+    Ok(fetch_namespaces(include_system, &mode, &settings)
+        .await?
+        .into_iter()
+        .filter_map(|ns| ns.metadata.name)
+        .collect())
+}
+
+/// Like `list_namespaces`, but also reports each namespace's phase.
+#[tauri::command]
+pub async fn list_namespaces_with_phase(
+    include_system: Option<bool>,
+    mode: State<'_, ClientMode>,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<NamespaceInfo>, String> {
+    Ok(fetch_namespaces(include_system, &mode, &settings)
+        .await?
+        .into_iter()
+        .map(namespace_info)
+        .collect())
+}
+
+/// Pod counts per namespace from a single cluster-wide list, for the
+/// namespace picker. `label_selector` scopes the count on large clusters;
+/// `by_status` also breaks each namespace down by pod status.
+#[tauri::command]
+pub async fn namespace_pod_counts(
+    source_file: String,
+    context_name: String,
+    label_selector: Option<String>,
+    by_status: Option<bool>,
+) -> Result<NamespacePodCounts, String> {
+    let mut params = ListParams::default();
+    if let Some(selector) = label_selector.as_deref().filter(|s| !s.is_empty()) {
+        params = params.labels(selector);
+    }
+
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<Pod> = Api::all(client);
+    let pods = api.list(&params).await.map_err(|e| e.to_string())?.items;
+
+    let mut result = NamespacePodCounts {
+        counts: HashMap::new(),
+        by_status: HashMap::new(),
+        total: pods.len(),
+    };
+    for pod in &pods {
+        let namespace = pod.metadata.namespace.clone().unwrap_or_default();
+        if by_status.unwrap_or(false) {
+            *result
+                .by_status
+                .entry(namespace.clone())
+                .or_default()
+                .entry(compute_pod_status(pod))
+                .or_default() += 1;
+        }
+        *result.counts.entry(namespace).or_default() += 1;
+    }
+    Ok(result)
+}
+
+/// How often long-lived watch and follow streams emit a heartbeat event, so
+/// the UI can tell a quiet stream from a dead one. It fires every interval,
+/// output or not; kubectl-backed streams (log follows, rollout watches) skip
+/// it once their kubectl process has exited.
+pub(crate) const STREAM_HEARTBEAT_INTERVAL: std::time::Duration =
+    std::time::Duration::from_secs(15);
+
+/// Server-side timeout for kube-rs watches. Shorter than common load balancer
+/// idle timeouts (60s on AWS ELB), so the server ends the watch before a
+/// middlebox silently drops it; the watcher then resumes transparently.
+const WATCH_TIMEOUT_SECS: u32 = 50;
+
+/// Streams namespace changes so the namespace picker stays fresh without
+/// polling. The current set is emitted first as one `namespace-list`, then
+/// live changes follow. Replaces any watch already running; ended by
+/// `stop_watch_namespaces`. Watch errors are retried with backoff.
+///
+/// After a relist (e.g. the resource version expired with 410 Gone) the
+/// watcher can't say what was deleted meanwhile, so `namespace-reset` is
+/// emitted and a fresh `namespace-list` replaces the whole set.
+///
+/// Events emitted:
+/// - `namespace-reset`       — payload: `null`            — a (re)list started
+/// - `namespace-list`        — payload: `NamespaceInfo[]` — the full set after a (re)list
+/// - `namespace-added`       — payload: `NamespaceInfo`   — created, or phase changed
+/// - `namespace-deleted`     — payload: `NamespaceInfo`
+/// - `namespace-watch-error` — payload: `String`
+/// - `watch-heartbeat`       — payload: `"namespaces"`    — every `STREAM_HEARTBEAT_INTERVAL`
+#[tauri::command]
+pub async fn watch_namespaces(
+    app: AppHandle,
+    mode: State<'_, ClientMode>,
+    watch: State<'_, crate::NamespaceWatch>,
+) -> Result<(), String> {
+    let client = build_client(&mode).await?;
+    let api: Api<K8sNamespace> = Api::all(client);
+
+    let task = tokio::spawn(async move {
+        let config = watcher::Config::default().timeout(WATCH_TIMEOUT_SECS);
+        let mut events = watcher(api, config).default_backoff().boxed();
+        let mut heartbeat = tokio::time::interval(STREAM_HEARTBEAT_INTERVAL);
+        let mut listed = Vec::new();
+        loop {
+            let event = tokio::select! {
+                event = events.next() => event,
+                _ = heartbeat.tick() => {
+                    let _ = app.emit("watch-heartbeat", "namespaces");
+                    continue;
+                }
+            };
+            let Some(event) = event else { break };
+            let _ = match event {
+                Ok(watcher::Event::Apply(ns)) => app.emit("namespace-added", namespace_info(ns)),
+                Ok(watcher::Event::Delete(ns)) => {
+                    app.emit("namespace-deleted", namespace_info(ns))
+                }
+                Ok(watcher::Event::Init) => {
+                    listed.clear();
+                    app.emit("namespace-reset", ())
+                }
+                Ok(watcher::Event::InitApply(ns)) => {
+                    listed.push(namespace_info(ns));
+                    Ok(())
+                }
+                Ok(watcher::Event::InitDone) => {
+                    app.emit("namespace-list", std::mem::take(&mut listed))
+                }
+                Err(e) => app.emit("namespace-watch-error", e.to_string()),
+            };
+        }
+    });
+
+    if let Some(previous) = watch.0.lock().map_err(|e| e.to_string())?.replace(task) {
+        previous.abort();
+    }
+    Ok(())
+}
+
+/// Stops the watch started by `watch_namespaces`.
+#[tauri::command]
+pub async fn stop_watch_namespaces(watch: State<'_, crate::NamespaceWatch>) -> Result<(), String> {
+    if let Some(task) = watch.0.lock().map_err(|e| e.to_string())?.take() {
+        task.abort();
+    }
+    Ok(())
+}
+
+/// Saves the prefixes `list_namespaces` treats as system namespaces, or
+/// restores the defaults (`kube-`, `openshift-`) when `prefixes` is None.
+#[tauri::command]
+pub async fn set_system_namespace_prefixes(
+    app: AppHandle,
+    prefixes: Option<Vec<String>>,
+    settings: State<'_, AppSettings>,
+) -> Result<(), String> {
+    let mut guard = settings.0.lock().map_err(|e| e.to_string())?;
+    guard.system_namespace_prefixes =
+        prefixes.map(|ps| ps.into_iter().filter(|p| !p.is_empty()).collect());
+    guard.save(&app)
+}
+
+/// Returns structured details for one pod — containers, conditions, QoS,
+/// owners — so the detail drawer doesn't have to parse `describe` output.
+/// Read from the context the row came from, not the active client mode.
+#[tauri::command]
+pub async fn get_pod(
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+) -> Result<PodDetail, String> {
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<Pod> = Api::namespaced(client, &namespace);
+    let pod = api.get(&name).await.map_err(|e| e.to_string())?;
+    Ok(pod_to_detail(pod))
+}
+
+/// Checks that `namespace/name` is still the pod with `uid`, so an action
+/// picked from a stale list doesn't hit a recreated pod of the same name
+/// (StatefulSets, or Deployments with fixed names). No `uid` skips the check.
+/// Failures start with "Gone:".
+pub(crate) async fn ensure_pod_uid(
+    source_file: &str,
+    context_name: &str,
+    namespace: &str,
+    name: &str,
+    uid: Option<&str>,
+) -> Result<(), String> {
+    let Some(uid) = uid.filter(|u| !u.is_empty()) else {
+        return Ok(());
+    };
+    let client = build_context_client(source_file, context_name).await?;
+    let pod = Api::<Pod>::namespaced(client, namespace)
+        .get_opt(name)
+        .await
+        .map_err(|e| e.to_string())?;
+    match pod.and_then(|p| p.metadata.uid) {
+        None => Err(format!("Gone: pod {namespace}/{name} no longer exists")),
+        Some(current) if current != uid => Err(format!(
+            "Gone: pod {namespace}/{name} was recreated since it was listed (uid changed)"
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Current name of the pod with `uid` in `namespace`, or None once it is
+/// gone. Field selectors can't match on uid, so the namespace is listed.
+#[tauri::command]
+pub async fn find_pod_by_uid(
+    uid: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+) -> Result<Option<String>, String> {
+    let client = build_context_client(&source_file, &context_name).await?;
+    let pods = Api::<Pod>::namespaced(client, &namespace)
+        .list(&ListParams::default())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(pods
+        .items
+        .into_iter()
+        .find(|p| p.metadata.uid.as_deref() == Some(uid.as_str()))
+        .and_then(|p| p.metadata.name))
+}
+
+/// Targets accepted by `wait_for_pod`.
+const WAIT_TARGETS: &[&str] = &["Ready", "Running", "Deleted"];
+
+/// Waits until a pod is Ready (its `Ready` condition is True), Running (phase)
+/// or Deleted, watching that one pod rather than polling. Returns the final
+/// status as shown in the pod table, or "Deleted". Timing out is reported as
+/// an error starting with "Timed out".
+#[tauri::command]
+pub async fn wait_for_pod(
+    name: String,
+    namespace: String,
+    target: String,
+    timeout_secs: u64,
+    source_file: String,
+    context_name: String,
+) -> Result<String, String> {
+    if !WAIT_TARGETS.contains(&target.as_str()) {
+        return Err(format!(
+            "Unsupported target '{target}' (expected one of: {})",
+            WAIT_TARGETS.join(", ")
+        ));
+    }
+
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<Pod> = Api::namespaced(client, &namespace);
+
+    let condition = |pod: Option<&Pod>| match (target.as_str(), pod) {
+        ("Deleted", pod) => pod.is_none(),
+        ("Running", Some(pod)) => {
+            pod.status.as_ref().and_then(|s| s.phase.as_deref()) == Some("Running")
+        }
+        ("Ready", Some(pod)) => pod
+            .status
+            .as_ref()
+            .and_then(|s| s.conditions.as_ref())
+            .is_some_and(|cs| cs.iter().any(|c| c.type_ == "Ready" && c.status == "True")),
+        _ => false,
+    };
+
+    let waited = tokio::time::timeout(
+        std::time::Duration::from_secs(timeout_secs),
+        await_condition(api, &name, condition),
+    )
+    .await
+    .map_err(|_| {
+        format!("Timed out after {timeout_secs}s waiting for pod {namespace}/{name} to be {target}")
+    })?
+    .map_err(|e| e.to_string())?;
+
+    Ok(waited
+        .as_ref()
+        .map(compute_pod_status)
+        .unwrap_or_else(|| "Deleted".to_string()))
+}
+
+/// QoS class, placement constraints and — for a Pending pod — the scheduler's
+/// reason it can't be placed, taken from the `PodScheduled` condition.
+#[tauri::command]
+pub async fn get_pod_scheduling(
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+) -> Result<PodScheduling, String> {
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<Pod> = Api::namespaced(client, &namespace);
+    let pod = api.get(&name).await.map_err(|e| e.to_string())?;
+
+    let spec = pod.spec.unwrap_or_default();
+    let status = pod.status.unwrap_or_default();
+
+    let unschedulable_reason = if status.phase.as_deref() == Some("Pending") {
+        status
+            .conditions
+            .iter()
+            .flatten()
+            .find(|c| c.type_ == "PodScheduled" && c.status == "False")
+            .and_then(|c| c.message.clone().or_else(|| c.reason.clone()))
+    } else {
+        None
+    };
+
+    Ok(PodScheduling {
+        qos_class: derive_qos_class(&spec).to_string(),
+        node: spec.node_name.clone(),
+        tolerations: spec.tolerations.iter().flatten().map(format_toleration).collect(),
+        node_selector: spec.node_selector.clone().unwrap_or_default().into_iter().collect(),
+        priority_class: spec.priority_class_name.clone(),
+        unschedulable_reason,
+    })
+}
+
+/// Why `container` last stopped, from `lastState.terminated` in its status —
+/// e.g. OOMKilled with exit code 137. None when the container has not
+/// restarted (or its previous state has been forgotten by the kubelet).
+#[tauri::command]
+pub async fn get_container_last_state(
+    pod: String,
+    namespace: String,
+    container: String,
+    source_file: String,
+    context_name: String,
+) -> Result<Option<ContainerTermination>, String> {
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<Pod> = Api::namespaced(client, &namespace);
+    let status = api.get(&pod).await.map_err(|e| e.to_string())?.status.unwrap_or_default();
+
+    let cs = status
+        .container_statuses
+        .iter()
+        .chain(status.init_container_statuses.iter())
+        .flatten()
+        .find(|cs| cs.name == container)
+        .ok_or_else(|| format!("Container '{container}' has no status in pod {namespace}/{pod}"))?;
+
+    Ok(cs
+        .last_state
+        .as_ref()
+        .and_then(|ls| ls.terminated.as_ref())
+        .map(|t| ContainerTermination {
+            exit_code: t.exit_code,
+            reason: t.reason.clone(),
+            signal: t.signal,
+            started_at: t.started_at.as_ref().map(|ts| ts.0.to_rfc3339()),
+            finished_at: t.finished_at.as_ref().map(|ts| ts.0.to_rfc3339()),
+        }))
+}
+
+/// Lists a pod's init and regular containers, init containers first (in the
+/// order they run), so the UI's container picker can label them.
+#[tauri::command]
+pub async fn list_pod_containers(
+    name: String,
+    namespace: String,
+    mode: State<'_, ClientMode>,
+) -> Result<Vec<ContainerInfo>, String> {
+    let client = build_client(&mode).await?;
+    let api: Api<Pod> = Api::namespaced(client, &namespace);
+    let pod = api.get(&name).await.map_err(|e| e.to_string())?;
+
+    let Some(spec) = pod.spec else {
+        return Ok(vec![]);
+    };
+
+    let init = spec
+        .init_containers
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| ContainerInfo { name: c.name, is_init: true });
+    let regular = spec
+        .containers
+        .into_iter()
+        .map(|c| ContainerInfo { name: c.name, is_init: false });
+
+    Ok(init.chain(regular).collect())
+}
+
+/// Image, resolved digest and pull policy for every container in a pod, init
+/// containers first. Comparing `image` with `image_id` shows which digest a
+/// floating tag like `latest` actually resolved to on the node.
+#[tauri::command]
+pub async fn get_pod_images(
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+) -> Result<Vec<ContainerImage>, String> {
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<Pod> = Api::namespaced(client, &namespace);
+    let pod = api.get(&name).await.map_err(|e| e.to_string())?;
+
+    let Some(spec) = pod.spec else {
+        return Ok(vec![]);
+    };
+    let status = pod.status.unwrap_or_default();
+    let image_id = |statuses: &Option<Vec<ContainerStatus>>, container: &str| {
+        statuses
+            .iter()
+            .flatten()
+            .find(|cs| cs.name == container)
+            .map(|cs| cs.image_id.clone())
+            .filter(|id| !id.is_empty())
+    };
+
+    let init = spec.init_containers.unwrap_or_default().into_iter().map(|c| ContainerImage {
+        image_id: image_id(&status.init_container_statuses, &c.name),
+        container: c.name,
+        is_init: true,
+        image: c.image.unwrap_or_default(),
+        pull_policy: c.image_pull_policy,
+    });
+    let regular = spec.containers.into_iter().map(|c| ContainerImage {
+        image_id: image_id(&status.container_statuses, &c.name),
+        container: c.name,
+        is_init: false,
+        image: c.image.unwrap_or_default(),
+        pull_policy: c.image_pull_policy,
+    });
+
+    Ok(init.chain(regular).collect())
+}
+
+/// Lines fetched per failing container by `get_job_logs`.
+const JOB_LOG_TAIL_LINES: i64 = 500;
+
+/// Containers of `pod` (init containers included) that are failing: the
+/// current run exited non-zero, or the previous one did and the container
+/// hasn't recovered — it's crash-looping or the pod phase is Failed. A
+/// container that failed once and then succeeded on retry isn't reported.
+fn container_failures(pod: &Pod) -> Vec<ContainerFailure> {
+    let Some(status) = pod.status.as_ref() else {
+        return vec![];
+    };
+    let pod_failed = status.phase.as_deref() == Some("Failed");
+    status
+        .init_container_statuses
+        .iter()
+        .chain(status.container_statuses.iter())
+        .flatten()
+        .filter_map(|cs| {
+            let state = cs.state.as_ref();
+            let current = state.and_then(|s| s.terminated.as_ref());
+            let last = cs.last_state.as_ref().and_then(|s| s.terminated.as_ref());
+            let crash_looping = state
+                .and_then(|s| s.waiting.as_ref())
+                .is_some_and(|w| w.reason.as_deref() == Some("CrashLoopBackOff"));
+            let (t, previous) = match (current, last) {
+                (Some(t), _) if t.exit_code != 0 => (t, false),
+                (None, Some(t)) if t.exit_code != 0 && (pod_failed || crash_looping) => (t, true),
+                _ => return None,
+            };
+            Some(ContainerFailure {
+                container: cs.name.clone(),
+                exit_code: t.exit_code,
+                reason: t.reason.clone(),
+                previous,
+            })
+        })
+        .collect()
+}
+
+/// Finds a Job's pods via the `job-name` label, oldest first, and reports
+/// which failed — phase Failed or a container `container_failures` reports.
+/// Failed pods carry the last `JOB_LOG_TAIL_LINES` lines of each failing
+/// container's logs; a log that can't be fetched is replaced by the error.
+#[tauri::command]
+pub async fn get_job_logs(
+    job_name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+) -> Result<Vec<JobPodLogs>, String> {
+    let client = build_context_client(&source_file, &context_name).await?;
+    let jobs: Api<Job> = Api::namespaced(client.clone(), &namespace);
+    jobs.get(&job_name)
+        .await
+        .map_err(|e| format!("Job {namespace}/{job_name}: {e}"))?;
+
+    let api: Api<Pod> = Api::namespaced(client, &namespace);
+    let params = ListParams::default().labels(&format!("job-name={job_name}"));
+    let mut pods = api.list(&params).await.map_err(|e| e.to_string())?.items;
+    pods.sort_by_key(|p| p.metadata.creation_timestamp.clone().map(|t| t.0));
+
+    let mut result = Vec::with_capacity(pods.len());
+    for pod in pods {
+        let name = pod.metadata.name.clone().unwrap_or_default();
+        let phase = pod
+            .status
+            .as_ref()
+            .and_then(|s| s.phase.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let failures = container_failures(&pod);
+        let failed = phase == "Failed" || !failures.is_empty();
+
+        let logs = if failed {
+            let mut out = String::new();
+            // A Failed pod whose containers report no exit code (e.g. evicted)
+            // still gets its default container's logs.
+            let targets: Vec<(Option<String>, bool)> = if failures.is_empty() {
+                vec![(None, false)]
+            } else {
+                failures.iter().map(|f| (Some(f.container.clone()), f.previous)).collect()
+            };
+            for (container, previous) in targets {
+                let params = LogParams {
+                    container: container.clone(),
+                    previous,
+                    tail_lines: Some(JOB_LOG_TAIL_LINES),
+                    ..LogParams::default()
+                };
+                let text = api.logs(&name, &params).await.unwrap_or_else(|e| e.to_string());
+                if let Some(c) = container {
+                    out.push_str(&format!("==> {c} <==\n"));
+                }
+                out.push_str(&text);
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+            }
+            Some(out)
+        } else {
+            None
+        };
+
+        result.push(JobPodLogs { name, phase, failed, failures, logs });
+    }
+
+    Ok(result)
+}
+
+/// Slack on top of the average restart interval before a container counts as
+/// settled; covers kubelet's crash-loop backoff, which caps at five minutes.
+const CRASH_LOOP_MAX_BACKOFF_SECS: f64 = 300.0;
+
+/// Shortest interval between restarts assumed: kubelet's first crash-loop
+/// backoff. Keeps timestamps a moment apart from reading as thousands per hour.
+const CRASH_LOOP_MIN_BACKOFF_SECS: f64 = 10.0;
+
+/// Restarts per hour for a container that restarted `count` times since
+/// `started`, the last time at `last`. While the time since the last restart
+/// is within twice the average interval (plus backoff slack) the container is
+/// still restarting at its average rate; after that the rate decays as one
+/// restart over the time since — so 50 restarts last week reads as near zero.
+/// The average interval is at least `CRASH_LOOP_MIN_BACKOFF_SECS`. A `last`
+/// before `started` (clock skew between nodes) isn't trusted and the lifetime
+/// average is used, as when `last` is missing.
+fn restarts_per_hour(
+    count: i32,
+    started: chrono::DateTime<Utc>,
+    last: Option<chrono::DateTime<Utc>>,
+    now: chrono::DateTime<Utc>,
+) -> f64 {
+    if count <= 0 {
+        return 0.0;
+    }
+    let secs = |from: chrono::DateTime<Utc>, to: chrono::DateTime<Utc>| {
+        (to - from).num_milliseconds().max(1000) as f64 / 1000.0
+    };
+    let average = |span: f64| (span / f64::from(count)).max(CRASH_LOOP_MIN_BACKOFF_SECS);
+    let Some(last) = last.filter(|last| *last >= started) else {
+        // lastState was lost (e.g. the node restarted): lifetime average.
+        return 3600.0 / average(secs(started, now));
+    };
+    let interval = average(secs(started, last));
+    let since_last = secs(last, now);
+    if since_last <= 2.0 * interval + CRASH_LOOP_MAX_BACKOFF_SECS {
+        3600.0 / interval
+    } else {
+        3600.0 / since_last
+    }
+}
+
+/// Estimated restarts per hour for each container of a pod, from its
+/// `restartCount`, `lastState.terminated.finishedAt` and the pod's start
+/// time (see `restarts_per_hour`). Containers at or above the
+/// `hotRestartsPerHour` setting are flagged `hot`.
+#[tauri::command]
+pub async fn container_restart_velocity(
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<RestartVelocity>, String> {
+    let threshold = settings.0.lock().map_err(|e| e.to_string())?.hot_restarts_per_hour();
+    let client = build_context_client(&source_file, &context_name).await?;
+    let pod = Api::<Pod>::namespaced(client, &namespace)
+        .get(&name)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let now = Utc::now();
+    let status = pod.status.unwrap_or_default();
+    let started = status
+        .start_time
+        .or(pod.metadata.creation_timestamp)
+        .map_or(now, |t| t.0);
+
+    Ok(status
+        .container_statuses
+        .unwrap_or_default()
+        .into_iter()
+        .map(|cs| {
+            let last = cs
+                .last_state
+                .and_then(|s| s.terminated)
+                .and_then(|t| t.finished_at);
+            let last_at = last.as_ref().map(|t| t.0);
+            let rate = restarts_per_hour(cs.restart_count, started, last_at, now);
+            RestartVelocity {
+                container: cs.name,
+                restart_count: cs.restart_count,
+                last_restart: last.as_ref().map(format_age),
+                restarts_per_hour: rate,
+                hot: rate >= threshold,
+            }
+        })
+        .collect())
+}
+
+/// Waiting reasons that mean the kubelet couldn't pull a container's image.
+const IMAGE_PULL_REASONS: &[&str] = &["ImagePullBackOff", "ErrImagePull"];
+
+/// Explains why a pod is stuck pulling an image: the first container (init
+/// containers included) waiting with `IMAGE_PULL_REASONS`, its kubelet message,
+/// and which of the pod's `imagePullSecrets` don't exist. Secrets the user
+/// isn't allowed to read are not reported missing.
+/// Fails when no container is failing to pull.
+#[tauri::command]
+pub async fn diagnose_image_pull(
+    pod: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+) -> Result<ImagePullDiagnosis, String> {
+    let client = build_context_client(&source_file, &context_name).await?;
+    let p = Api::<Pod>::namespaced(client.clone(), &namespace)
+        .get(&pod)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = p.status.unwrap_or_default();
+    let (cs, waiting) = status
+        .init_container_statuses
+        .unwrap_or_default()
+        .into_iter()
+        .chain(status.container_statuses.unwrap_or_default())
+        .find_map(|cs| {
+            let waiting = cs.state.as_ref()?.waiting.clone()?;
+            let reason = waiting.reason.as_deref()?;
+            IMAGE_PULL_REASONS.contains(&reason).then_some((cs, waiting))
+        })
+        .ok_or_else(|| format!("Pod {namespace}/{pod} is not failing to pull an image"))?;
+
+    let referenced_secrets: Vec<String> = p
+        .spec
+        .and_then(|s| s.image_pull_secrets)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|r| r.name)
+        .filter(|n| !n.is_empty())
+        .collect();
+
+    let secrets = Api::<Secret>::namespaced(client, &namespace);
+    let mut missing_secrets = Vec::new();
+    for name in &referenced_secrets {
+        if let Ok(None) = secrets.get_metadata_opt(name).await {
+            missing_secrets.push(name.clone());
+        }
+    }
+
+    Ok(ImagePullDiagnosis {
+        container: cs.name,
+        image: cs.image,
+        reason: waiting.reason.unwrap_or_default(),
+        message: waiting.message,
+        referenced_secrets,
+        missing_secrets,
+    })
+}
+
+/// Creates a namespace in the given context after validating the name.
+#[tauri::command]
+pub async fn create_namespace(
+    name: String,
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+) -> Result<(), String> {
+    ensure_writable(&settings, "Creating namespaces")?;
+    validate_namespace_name(&name)?;
+
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<K8sNamespace> = Api::all(client);
+
+    let ns = K8sNamespace {
+        metadata: kube::api::ObjectMeta {
+            name: Some(name),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    api.create(&PostParams::default(), &ns)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Requests deletion of a namespace and returns immediately — the namespace
+/// stays in `Terminating` until its contents are gone, which the next
+/// `list_namespaces` refresh will show.
+///
+/// Protected namespaces (`default`, `kube-system`, …) are refused unless
+/// `confirm` is `true`.
+#[tauri::command]
+pub async fn delete_namespace(
+    name: String,
+    source_file: String,
+    context_name: String,
+    confirm: Option<bool>,
+    settings: State<'_, AppSettings>,
+) -> Result<(), String> {
+    ensure_writable(&settings, "Deleting namespaces")?;
+    validate_namespace_name(&name)?;
+
+    if PROTECTED_NAMESPACES.contains(&name.as_str()) && !confirm.unwrap_or(false) {
+        return Err(format!(
+            "Refusing to delete protected namespace '{name}' without confirmation"
+        ));
+    }
+
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<K8sNamespace> = Api::all(client);
+    api.delete(&name, &DeleteParams::default())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Counts the objects of kind `K` in `namespace` with a metadata-only list,
+/// so secrets' data never leaves the API server. Errors (typically 403) are
+/// reported in the result rather than failing the whole summary.
+async fn count_in_namespace<K>(client: Client, namespace: &str, limit: &Semaphore) -> KindCount
+where
+    K: kube::Resource<Scope = k8s_openapi::NamespaceResourceScope, DynamicType = ()>
+        + Clone
+        + serde::de::DeserializeOwned
+        + std::fmt::Debug,
+{
+    let _permit = limit.acquire().await;
+    let result = Api::<K>::namespaced(client, namespace)
+        .list_metadata(&ListParams::default())
+        .await;
+    let (count, error) = match result {
+        Ok(list) => (Some(list.items.len()), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    KindCount { kind: K::plural(&()).into_owned(), count, error }
+}
+
+/// What deleting `namespace` would take with it: the number of pods,
+/// deployments, statefulsets, services, PVCs and secrets, listed
+/// concurrently (up to the `maxConcurrentRequests` setting). Kinds the user
+/// can't list come back with `error` set instead of failing the whole summary.
+#[tauri::command]
+pub async fn namespace_contents_summary(
+    namespace: String,
+    source_file: String,
+    context_name: String,
+    limiter: State<'_, crate::RequestLimiter>,
+) -> Result<Vec<KindCount>, String> {
+    validate_namespace_name(&namespace)?;
+    let limit = request_limiter(&limiter)?;
+    let client = build_context_client(&source_file, &context_name).await?;
+    let (ns, limit) = (namespace.as_str(), limit.as_ref());
+
+    let (pods, deployments, statefulsets, services, pvcs, secrets) = tokio::join!(
+        count_in_namespace::<Pod>(client.clone(), ns, limit),
+        count_in_namespace::<Deployment>(client.clone(), ns, limit),
+        count_in_namespace::<StatefulSet>(client.clone(), ns, limit),
+        count_in_namespace::<Service>(client.clone(), ns, limit),
+        count_in_namespace::<PersistentVolumeClaim>(client.clone(), ns, limit),
+        count_in_namespace::<Secret>(client, ns, limit),
+    );
+    Ok(vec![pods, deployments, statefulsets, services, pvcs, secrets])
+}
+
+/// Where a deployment's rollout stands, judged like `kubectl rollout status`,
+/// plus replica counts for a progress bar. A single snapshot — poll it while
+/// the rollout runs (`watch_rollout` streams kubectl's text instead). A
+/// `Progressing` condition with reason ProgressDeadlineExceeded is reported
+/// as phase "stalled".
+#[tauri::command]
+pub async fn deployment_rollout_progress(
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+) -> Result<RolloutProgress, String> {
+    let client = build_context_client(&source_file, &context_name).await?;
+    let deploy = Api::<Deployment>::namespaced(client, &namespace)
+        .get(&name)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let spec = deploy.spec.unwrap_or_default();
+    let status = deploy.status.unwrap_or_default();
+    let total = spec.replicas.unwrap_or(1);
+    let updated = status.updated_replicas.unwrap_or(0);
+    let available = status.available_replicas.unwrap_or(0);
+    let current = status.replicas.unwrap_or(0);
+    let progressing = status
+        .conditions
+        .iter()
+        .flatten()
+        .find(|c| c.type_ == "Progressing");
+
+    let observed = deploy.metadata.generation <= status.observed_generation;
+    let (phase, message) = if !observed {
+        ("pending", "Waiting for the deployment spec update to be observed".to_string())
+    } else if spec.paused.unwrap_or(false) {
+        ("paused", "Rollout is paused".to_string())
+    } else if progressing.is_some_and(|c| c.reason.as_deref() == Some("ProgressDeadlineExceeded")) {
+        let detail = progressing.and_then(|c| c.message.clone()).unwrap_or_default();
+        ("stalled", format!("Rollout exceeded its progress deadline: {detail}"))
+    } else if updated < total {
+        ("progressing", format!("{updated} of {total} new replicas have been updated"))
+    } else if current > updated {
+        let old = current - updated;
+        ("progressing", format!("{old} old replicas are pending termination"))
+    } else if available < updated {
+        ("progressing", format!("{available} of {updated} updated replicas are available"))
+    } else {
+        ("complete", "Rollout complete".to_string())
+    };
+
+    Ok(RolloutProgress { phase: phase.to_string(), updated, available, total, message })
+}
+
+// ── Snapshots ─────────────────────────────────────────────────────────────────
+
+/// `ClusterSnapshot.version` written by `snapshot_cluster`.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Lists every `K` cluster-wide as JSON with `metadata.managedFields` removed.
+async fn raw_objects<K>(client: Client) -> Result<Vec<serde_json::Value>, String>
+where
+    K: kube::Resource<DynamicType = ()>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + std::fmt::Debug,
+{
+    let list = Api::<K>::all(client)
+        .list(&ListParams::default())
+        .await
+        .map_err(|e| e.to_string())?;
+    list.items
+        .into_iter()
+        .map(|mut obj| {
+            obj.meta_mut().managed_fields = None;
+            serde_json::to_value(obj).map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+/// Runs `list` only when `include` is set; a failure (e.g. RBAC forbids
+/// listing nodes) comes back as the error string instead of failing the
+/// snapshot.
+async fn optional_kind(
+    include: Option<bool>,
+    list: impl Future<Output = Result<Vec<serde_json::Value>, String>>,
+) -> (Option<Vec<serde_json::Value>>, Option<String>) {
+    if !include.unwrap_or(false) {
+        return (None, None);
+    }
+    match list.await {
+        Ok(objects) => (Some(objects), None),
+        Err(e) => (None, Some(e)),
+    }
+}
+
+/// Freezes the cluster's state for a postmortem: pod summaries from every
+/// namespace (with metrics when available) and, with `include_deployments`
+/// / `include_nodes`, the raw objects of those kinds, written as JSON to
+/// `path` with a timestamp and the context it came from. Returns the path.
+/// A kind the user can't list is recorded in `deploymentsError` /
+/// `nodesError` and the rest of the snapshot is still written.
+///
+/// `path` is any local path the frontend picked; this reads the cluster
+/// only, so it doesn't go through `ensure_writable`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn snapshot_cluster(
+    app: AppHandle,
+    source_file: String,
+    context_name: String,
+    path: String,
+    include_deployments: Option<bool>,
+    include_nodes: Option<bool>,
+    settings: State<'_, AppSettings>,
+) -> Result<String, String> {
+    let label_prefixes = settings.0.lock().map_err(|e| e.to_string())?.summary_label_prefixes();
+    let client = build_context_client(&source_file, &context_name).await?;
+    let taken_at = Utc::now().to_rfc3339();
+
+    let params = ListParams::default();
+    let pods =
+        list_pod_summaries(&app, client.clone(), None, &params, None, &label_prefixes).await?;
+    let (deployments, deployments_error) =
+        optional_kind(include_deployments, raw_objects::<Deployment>(client.clone())).await;
+    let (nodes, nodes_error) = optional_kind(include_nodes, raw_objects::<Node>(client)).await;
+
+    let server_url = context_connection_details(context_name.clone(), source_file.clone())
+        .await
+        .ok()
+        .and_then(|details| details.server);
+
+    let snapshot = ClusterSnapshot {
+        version: SNAPSHOT_VERSION,
+        taken_at,
+        server_url,
+        context_name,
+        source_file,
+        pods,
+        deployments,
+        deployments_error,
+        nodes,
+        nodes_error,
+    };
+    let json = serde_json::to_string_pretty(&snapshot)
+        .map_err(|e| format!("Failed to serialize snapshot: {e}"))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {path}: {e}"))?;
+    Ok(path)
+}
+
+/// Reads a file written by `snapshot_cluster` for read-only viewing.
+#[tauri::command]
+pub async fn load_snapshot(path: String) -> Result<ClusterSnapshot, String> {
+    let raw = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    let snapshot: ClusterSnapshot =
+        serde_json::from_str(&raw).map_err(|e| format!("Not a cluster snapshot: {e}"))?;
+    if snapshot.version > SNAPSHOT_VERSION {
+        return Err(format!(
+            "Snapshot format v{} is newer than this app supports (v{SNAPSHOT_VERSION})",
+            snapshot.version
+        ));
+    }
+    Ok(snapshot)
+}
+
+// ── Template drift ────────────────────────────────────────────────────────────
+
+/// Owner kinds whose pod template `pod_template_drift` can compare against.
+const TEMPLATE_OWNERS: &[&str] = &["ReplicaSet", "StatefulSet", "DaemonSet", "Job"];
+
+/// The drift-relevant fields of `spec` — image, env and resources of every
+/// container — one "container/<name> <field>" line each, in a stable order.
+fn drift_lines(spec: &PodSpec) -> Vec<String> {
+    let init = spec.init_containers.iter().flatten().map(|c| ("init-container", c));
+    let regular = spec.containers.iter().map(|c| ("container", c));
+
+    let mut lines = Vec::new();
+    for (kind, c) in init.chain(regular) {
+        let prefix = format!("{kind}/{}", c.name);
+        lines.push(format!("{prefix} image: {}", c.image.as_deref().unwrap_or_default()));
+
+        let mut env: Vec<String> = c
+            .env
+            .iter()
+            .flatten()
+            .map(|e| match (&e.value, &e.value_from) {
+                (_, Some(from)) => format!(
+                    "{}=<from {}>",
+                    e.name,
+                    serde_json::to_string(from).unwrap_or_default()
+                ),
+                (value, None) => format!("{}={}", e.name, value.as_deref().unwrap_or_default()),
+            })
+            .collect();
+        env.sort();
+        lines.extend(env.into_iter().map(|e| format!("{prefix} env {e}")));
+
+        if let Some(res) = &c.resources {
+            for (section, map) in [("requests", &res.requests), ("limits", &res.limits)] {
+                let mut entries: Vec<_> = map.iter().flatten().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                for (key, q) in entries {
+                    lines.push(format!("{prefix} resources.{section}.{key}: {}", q.0));
+                }
+            }
+        }
+    }
+    lines
+}
+
+/// Line diff of `old` → `new` via longest common subsequence: unchanged lines
+/// prefixed "  ", removed "- ", added "+ ". A changed line reads as its
+/// removal followed by its replacement.
+fn line_diff(old: &[String], new: &[String]) -> Vec<String> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            out.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            out.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+/// Compares a pod with the template of the controller that created it
+/// (ReplicaSet, StatefulSet, DaemonSet or Job) to flag hand-edited pods. Only
+/// each container's image, env and resources are compared. Values filled in
+/// at admission — LimitRange defaults, injected sidecars — also show as drift.
+#[tauri::command]
+pub async fn pod_template_drift(
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+) -> Result<PodTemplateDrift, String> {
+    let client = build_context_client(&source_file, &context_name).await?;
+    let pod = Api::<Pod>::namespaced(client.clone(), &namespace)
+        .get(&name)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let owner = controller_ref(pod.metadata.owner_references.as_ref())
+        .filter(|o| TEMPLATE_OWNERS.contains(&o.kind.as_str()))
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "Pod {namespace}/{name} has no owner with a pod template ({})",
+                TEMPLATE_OWNERS.join(", ")
+            )
+        })?;
+
+    let template = match owner.kind.as_str() {
+        "ReplicaSet" => Api::<ReplicaSet>::namespaced(client, &namespace)
+            .get(&owner.name)
+            .await
+            .map(|o| o.spec.and_then(|s| s.template)),
+        "StatefulSet" => Api::<StatefulSet>::namespaced(client, &namespace)
+            .get(&owner.name)
+            .await
+            .map(|o| o.spec.map(|s| s.template)),
+        "DaemonSet" => Api::<DaemonSet>::namespaced(client, &namespace)
+            .get(&owner.name)
+            .await
+            .map(|o| o.spec.map(|s| s.template)),
+        _ => Api::<Job>::namespaced(client, &namespace)
+            .get(&owner.name)
+            .await
+            .map(|o| o.spec.map(|s| s.template)),
+    }
+    .map_err(|e| format!("{} {namespace}/{}: {e}", owner.kind, owner.name))?;
+
+    let template_spec = template
+        .and_then(|t| t.spec)
+        .ok_or_else(|| format!("{} {} has no pod template", owner.kind, owner.name))?;
+    let pod_spec = pod.spec.unwrap_or_default();
+
+    let diff = line_diff(&drift_lines(&template_spec), &drift_lines(&pod_spec));
+    Ok(PodTemplateDrift {
+        owner: OwnerRef { kind: owner.kind, name: owner.name },
+        drifted: diff.iter().any(|l| !l.starts_with("  ")),
+        diff,
+    })
+}
+
+/// Read-only preview for the delete confirmation dialog: who manages the pod
+/// (so the UI can warn it will be recreated) and whether a
+/// PodDisruptionBudget would block evicting it.
+#[tauri::command]
+pub async fn preview_delete_pod(
+    name: String,
+    namespace: String,
+    mode: State<'_, ClientMode>,
+) -> Result<DeletePreview, String> {
+    let client = build_client(&mode).await?;
+    let pods: Api<Pod> = Api::namespaced(client.clone(), &namespace);
+    let pod = pods.get(&name).await.map_err(|e| e.to_string())?;
+
+    let direct_owner = controller_ref(pod.metadata.owner_references.as_ref()).cloned();
+    let will_be_recreated = direct_owner
+        .as_ref()
+        .is_some_and(|o| RECREATING_OWNERS.contains(&o.kind.as_str()));
+
+    // A ReplicaSet is usually an implementation detail of a Deployment —
+    // report the Deployment when there is one.
+    let mut owner = direct_owner;
+    if let Some(o) = owner.as_ref().filter(|o| o.kind == "ReplicaSet") {
+        let rs_api: Api<ReplicaSet> = Api::namespaced(client.clone(), &namespace);
+        if let Ok(rs) = rs_api.get(&o.name).await {
+            if let Some(parent) = controller_ref(rs.metadata.owner_references.as_ref()) {
+                owner = Some(parent.clone());
+            }
+        }
+    }
+
+    let labels = pod.metadata.labels.unwrap_or_default();
+    let pdb_api: Api<PodDisruptionBudget> = Api::namespaced(client, &namespace);
+    let blocking_pdbs: Vec<String> = pdb_api
+        .list(&ListParams::default())
+        .await
+        .map_err(|e| e.to_string())?
+        .items
+        .into_iter()
+        .filter(|pdb| {
+            let matches = pdb
+                .spec
+                .as_ref()
+                .and_then(|s| s.selector.as_ref())
+                .is_some_and(|sel| selector_matches(sel, &labels));
+            let allowed = pdb.status.as_ref().map(|s| s.disruptions_allowed).unwrap_or(0);
+            matches && allowed <= 0
+        })
+        .filter_map(|pdb| pdb.metadata.name)
+        .collect();
+
+    Ok(DeletePreview {
+        owner: owner.map(|o| OwnerRef { kind: o.kind, name: o.name }),
+        will_be_recreated,
+        eviction_blocked: !blocking_pdbs.is_empty(),
+        blocking_pdbs,
+    })
+}
+
+/// Deletes a pod by name and namespace using kube-rs.
+///
+/// The pod's controlling owner is read first and returned with the result,
+/// so the UI can say "deleted — ReplicaSet will recreate it" instead of the
+/// pod's reappearance looking like a failed delete.
+#[tauri::command]
+pub async fn delete_pod(
+    name: String,
+    namespace: String,
+    mode: State<'_, ClientMode>,
+    settings: State<'_, AppSettings>,
+) -> Result<DeletePodResult, String> {
+    ensure_writable(&settings, "Deleting pods")?;
+    let client = build_client(&mode).await?;
+    let api: Api<Pod> = Api::namespaced(client, &namespace);
+
+    let pod = api.get(&name).await.map_err(|e| e.to_string())?;
+    let owners = pod.metadata.owner_references.unwrap_or_default();
+    let owner = owners
+        .iter()
+        .find(|o| o.controller == Some(true))
+        .or_else(|| owners.first());
+
+    api.delete(&name, &DeleteParams::default())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(DeletePodResult {
+        deleted: true,
+        owner_kind: owner.map(|o| o.kind.clone()),
+        owner_name: owner.map(|o| o.name.clone()),
+    })
+}
+
+/// Evicts a pod through the `pods/eviction` subresource. Unlike `delete_pod`
+/// this honours PodDisruptionBudgets, so it is the safe way to move a single
+/// pod off a node in production. The API server answers 429 when a budget
+/// would be violated; that case gets its own message so the UI can tell
+/// "try again later" apart from a real failure.
+#[tauri::command]
+pub async fn evict_pod(
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+) -> Result<(), String> {
+    ensure_writable(&settings, "Evicting pods")?;
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<Pod> = Api::namespaced(client, &namespace);
+    match api.evict(&name, &EvictParams::default()).await {
+        Ok(_) => Ok(()),
+        Err(kube::Error::Api(e)) if e.code == 429 => Err(format!(
+            "Eviction of {namespace}/{name} blocked by a PodDisruptionBudget: {}",
+            e.message
+        )),
+        Err(e) => Err(format!("Eviction of {namespace}/{name} failed: {e}")),
+    }
+}
+
+/// When a pod finished: its containers' latest `finishedAt`, else its start
+/// time, else its creation time.
+fn pod_finished_at(pod: &Pod) -> Option<Time> {
+    let status = pod.status.as_ref();
+    status
+        .into_iter()
+        .flat_map(|s| s.init_container_statuses.iter().chain(s.container_statuses.iter()))
+        .flatten()
+        .filter_map(|cs| cs.state.as_ref()?.terminated.as_ref()?.finished_at.clone())
+        .max_by_key(|t| t.0)
+        .or_else(|| status.and_then(|s| s.start_time.clone()))
+        .or_else(|| pod.metadata.creation_timestamp.clone())
+}
+
+/// Deletes pods in a terminal phase (Succeeded / Failed — shown as
+/// Completed, Error, OOMKilled, … by `compute_pod_status`), e.g. leftovers
+/// of finished Jobs. `namespace` None covers every namespace; `older_than`
+/// ("30m", "7d", …) keeps pods that finished more recently. With `dry_run`
+/// nothing is deleted and the matching pods are only listed.
+#[tauri::command]
+pub async fn cleanup_finished_pods(
+    namespace: Option<String>,
+    older_than: Option<String>,
+    dry_run: bool,
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+) -> Result<Vec<FinishedPod>, String> {
+    if !dry_run {
+        ensure_writable(&settings, "Deleting pods")?;
+    }
+    let cutoff = older_than
+        .as_deref()
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let age = parse_duration(s)?;
+            Utc::now().checked_sub_signed(age).ok_or_else(|| invalid_duration(s))
+        })
+        .transpose()?;
+
+    let client = build_context_client(&source_file, &context_name).await?;
+    let api: Api<Pod> = match namespace.as_deref().filter(|ns| !ns.is_empty()) {
+        Some(ns) => Api::namespaced(client.clone(), ns),
+        None => Api::all(client.clone()),
+    };
+    let params = ListParams::default().fields("status.phase!=Running,status.phase!=Pending");
+    let pods = api.list(&params).await.map_err(|e| e.to_string())?.items;
+
+    let mut finished = Vec::new();
+    for pod in pods {
+        let phase = pod.status.as_ref().and_then(|s| s.phase.as_deref());
+        let status = compute_pod_status(&pod);
+        if !matches!(phase, Some("Succeeded" | "Failed")) || status == "Terminating" {
+            continue;
+        }
+        let Some(finished_at) = pod_finished_at(&pod) else { continue };
+        if cutoff.is_some_and(|cutoff| finished_at.0 > cutoff) {
+            continue;
+        }
+
+        let name = pod.metadata.name.clone().unwrap_or_default();
+        let ns = pod.metadata.namespace.clone().unwrap_or_default();
+        let error = if dry_run {
+            None
+        } else {
+            Api::<Pod>::namespaced(client.clone(), &ns)
+                .delete(&name, &DeleteParams::default())
+                .await
+                .err()
+                .map(|e| e.to_string())
+        };
+        finished.push(FinishedPod {
+            name,
+            namespace: ns,
+            status,
+            finished: format_age(&finished_at),
+            error,
+        });
+    }
+
+    Ok(finished)
+}
+
+// ── Generic resources ─────────────────────────────────────────────────────────
+
+/// Fetches any object — built-in or CRD — and returns it as YAML with
+/// `metadata.managedFields` stripped. `api_version` is as in a manifest
+/// ("v1", "apps/v1", "cert-manager.io/v1"); the plural and scope come from
+/// API discovery. `namespace` is required for namespaced kinds and ignored for
+/// cluster-scoped ones. Unknown kinds and missing objects return kube's error.
+#[tauri::command]
+pub async fn get_resource_yaml(
+    api_version: String,
+    kind: String,
+    name: String,
+    namespace: Option<String>,
+    source_file: String,
+    context_name: String,
+) -> Result<String, String> {
+    let (group, version) = api_version.rsplit_once('/').unwrap_or(("", &api_version));
+    let gvk = GroupVersionKind::gvk(group, version, &kind);
+
+    let client = build_context_client(&source_file, &context_name).await?;
+    let (resource, caps) = kube::discovery::pinned_kind(&client, &gvk)
+        .await
+        .map_err(|e| e.to_string())?;
+    let api = dynamic_api(client, &resource, &caps, namespace.as_deref())?;
+
+    let mut obj = api.get(&name).await.map_err(|e| e.to_string())?;
+    obj.metadata.managed_fields = None;
+    serde_yaml::to_string(&obj).map_err(|e| format!("Failed to serialize {kind}/{name}: {e}"))
+}
+
+/// A dynamic Api for `resource`, scoped to `namespace` for namespaced kinds.
+fn dynamic_api(
+    client: Client,
+    resource: &ApiResource,
+    caps: &ApiCapabilities,
+    namespace: Option<&str>,
+) -> Result<Api<DynamicObject>, String> {
+    match (&caps.scope, namespace) {
+        (Scope::Cluster, _) => Ok(Api::all_with(client, resource)),
+        (Scope::Namespaced, Some(ns)) if !ns.is_empty() => {
+            Ok(Api::namespaced_with(client, ns, resource))
+        }
+        (Scope::Namespaced, _) => {
+            Err(format!("{} is namespaced; a namespace is required", resource.kind))
+        }
+    }
+}
+
+/// Picks the entry of a `list_api_resources` listing that `kind` names, the
+/// way kubectl does: the Kind ("Deployment"), plural ("deployments") or a
+/// short name ("deploy"), case-insensitively, optionally qualified with its
+/// group ("deployments.apps"). When several groups serve the name, the core
+/// group wins, then the first in kubectl's own (priority) order —
+/// `events` is core v1, not events.k8s.io.
+fn pick_api_resource<'a>(
+    resources: &'a [ApiResourceInfo],
+    kind: &str,
+) -> Option<&'a ApiResourceInfo> {
+    let kind = kind.to_lowercase();
+    let (name, group) = kind.split_once('.').map_or((kind.as_str(), None), |(n, g)| (n, Some(g)));
+    fn group_of(r: &ApiResourceInfo) -> &str {
+        r.api_version.rsplit_once('/').map_or("", |(g, _)| g)
+    }
+
+    let mut matches = resources.iter().filter(|r| {
+        (r.kind.to_lowercase() == name || r.name == name || r.shortnames.iter().any(|s| s == name))
+            && group.map_or(true, |g| group_of(r) == g)
+    });
+    let first = matches.next()?;
+    Some(std::iter::once(first).chain(matches).find(|r| group_of(r).is_empty()).unwrap_or(first))
+}
+
+/// Resolves `kind` (see `pick_api_resource`) against the cached
+/// `list_api_resources` listing, then discovers only that group/version.
+/// Without kubectl a full kube discovery is used instead, walked in
+/// alphabetical group order so the core group still comes first.
+async fn resolve_kind(
+    client: &Client,
+    kind: &str,
+    listing: Result<Vec<ApiResourceInfo>, String>,
+) -> Result<(ApiResource, ApiCapabilities), String> {
+    let not_found = || format!("the server doesn't have a resource type \"{kind}\"");
+    if let Ok(resources) = listing {
+        let found = pick_api_resource(&resources, kind).ok_or_else(not_found)?;
+        let (group, version) =
+            found.api_version.rsplit_once('/').unwrap_or(("", &found.api_version));
+        let gvk = GroupVersionKind::gvk(group, version, &found.kind);
+        return kube::discovery::pinned_kind(client, &gvk).await.map_err(|e| e.to_string());
+    }
+
+    let lower = kind.to_lowercase();
+    let (name, group) = lower.split_once('.').map_or((lower.as_str(), None), |(n, g)| (n, Some(g)));
+    let mut discovery = kube::Discovery::new(client.clone());
+    if let Some(group) = group {
+        discovery = discovery.filter(&[group]);
+    }
+    let discovery = discovery.run().await.map_err(|e| e.to_string())?;
+
+    let found = discovery
+        .groups_alphabetical()
+        .into_iter()
+        .flat_map(|g| g.recommended_resources())
+        .find(|(ar, _)| ar.kind.to_lowercase() == name || ar.plural == name);
+    found.ok_or_else(not_found)
+}
+
+/// Patch flavours accepted by `patch_resource`, as for `kubectl patch --type`.
+const PATCH_TYPES: &[&str] = &["json", "merge", "strategic"];
+
+/// Applies `patch` to one object of any kind and returns its new
+/// resourceVersion. `patch_type` picks the format:
+/// - "json"      — RFC 6902 operation list, e.g. `[{"op":"remove","path":"/metadata/labels/x"}]`
+/// - "merge"     — RFC 7386 merge patch, e.g. `{"metadata":{"labels":{"tier":"web"}}}`
+/// - "strategic" — Kubernetes strategic merge; built-in kinds only, CRDs reject it
+///
+/// `kind` is resolved as in `resolve_kind`, reusing the `list_api_resources`
+/// cache; `namespace` is required for namespaced kinds.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn patch_resource(
+    kind: String,
+    name: String,
+    namespace: Option<String>,
+    patch_type: String,
+    patch: String,
+    source_file: String,
+    context_name: String,
+    settings: State<'_, AppSettings>,
+    cache: State<'_, ApiResourcesCache>,
+) -> Result<String, String> {
+    ensure_writable(&settings, "Patching resources")?;
+    let value: serde_json::Value =
+        serde_json::from_str(&patch).map_err(|e| format!("Patch is not valid JSON: {e}"))?;
+    let patch = match patch_type.as_str() {
+        "json" => Patch::Json(
+            serde_json::from_value::<json_patch::Patch>(value)
+                .map_err(|e| format!("Invalid JSON patch: {e}"))?,
+        ),
+        "merge" => Patch::Merge(value),
+        "strategic" => Patch::Strategic(value),
+        other => {
+            return Err(format!(
+                "Unknown patch type \"{other}\": expected one of {}",
+                PATCH_TYPES.join(", ")
+            ))
+        }
+    };
+
+    let listing =
+        list_api_resources(source_file.clone(), context_name.clone(), None, settings, cache).await;
+    let client = build_context_client(&source_file, &context_name).await?;
+    let (resource, caps) = resolve_kind(&client, &kind, listing).await?;
+    let api = dynamic_api(client, &resource, &caps, namespace.as_deref())?;
+
+    let obj = api
+        .patch(&name, &PatchParams::default(), &patch)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(obj.metadata.resource_version.unwrap_or_default())
+}
+
+// ── Session recording ─────────────────────────────────────────────────────────
+
+/// Initial PTY size; the frontend sends the real size via `resize_pty`.
+const DEFAULT_PTY_ROWS: u16 = 24;
+const DEFAULT_PTY_COLS: u16 = 80;
+
+/// How long PTY output is gathered into one `exec-output` event. Starts at
+/// the minimum (imperceptible when typing) and doubles, up to the maximum,
+/// whenever an emit fails or takes longer than the window — e.g. under
+/// `yes` — then halves back once the UI keeps up.
+const PTY_COALESCE_MIN: std::time::Duration = std::time::Duration::from_millis(4);
+const PTY_COALESCE_MAX: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Largest `exec-output` payload; a full batch is emitted without waiting.
+const PTY_MAX_BATCH: usize = 64 * 1024;
+
+/// Consecutive failed emits after which the session output is abandoned.
+const PTY_MAX_EMIT_FAILURES: u32 = 5;
+
+/// PTY reads queued for the emitter. When it falls behind, the reader blocks,
+/// the PTY fills up and the remote process is throttled instead of memory
+/// growing.
+const PTY_QUEUE_CHUNKS: usize = 16;
+
+/// The coalescing window after an emit: doubled when it was `slow`, halved
+/// otherwise, within `PTY_COALESCE_MIN..=PTY_COALESCE_MAX`.
+fn next_coalesce_window(window: std::time::Duration, slow: bool) -> std::time::Duration {
+    if slow {
+        (window * 2).min(PTY_COALESCE_MAX)
+    } else {
+        (window / 2).max(PTY_COALESCE_MIN)
+    }
+}
+
+/// Passes PTY output chunks received on `rx` to `emit` (which sends
+/// `exec-output` and reports whether that worked), batching chunks that
+/// arrive within the coalescing window (see `PTY_COALESCE_MIN`). Returns when
+/// the reader side hangs up or emitting keeps failing. A failed emit still
+/// advances `seq` / `offset` so the frontend sees the gap.
+fn forward_pty_output(
+    rx: std::sync::mpsc::Receiver<Vec<u8>>,
+    recording: &std::sync::Mutex<Option<CastRecorder>>,
+    mut emit: impl FnMut(ExecOutput) -> bool,
+) {
+    use std::sync::mpsc::RecvTimeoutError;
+
+    let mut pending = Vec::new();
+    let (mut seq, mut offset) = (0u64, 0u64);
+    let mut window = PTY_COALESCE_MIN;
+    let mut failures = 0;
+
+    while let Ok(chunk) = rx.recv() {
+        pending.extend_from_slice(&chunk);
+        let deadline = Instant::now() + window;
+        let mut closed = false;
+        while pending.len() < PTY_MAX_BATCH {
+            let left = deadline.saturating_duration_since(Instant::now());
+            match rx.recv_timeout(left) {
+                Ok(chunk) => pending.extend_from_slice(&chunk),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    closed = true;
+                    break;
+                }
+            }
+        }
+
+        let data = take_complete_utf8(&mut pending);
+        if !data.is_empty() {
+            if let Ok(mut rec) = recording.lock() {
+                if let Some(rec) = rec.as_mut() {
+                    rec.event("o", &data);
+                }
+            }
+            let len = data.len() as u64;
+            let started = Instant::now();
+            let sent = emit(ExecOutput { seq, offset, data });
+            window = next_coalesce_window(window, !sent || started.elapsed() > window);
+            seq += 1;
+            offset += len;
+            failures = if sent { 0 } else { failures + 1 };
+            if failures >= PTY_MAX_EMIT_FAILURES {
+                return;
+            }
+        }
+        if closed {
+            return;
+        }
+    }
+}
+
+/// Writes an exec session as an asciinema v2 cast: a JSON header line, then
+/// one `[elapsed_secs, code, data]` line per event ("o" = output, "r" = resize).
+pub struct CastRecorder {
+    path: PathBuf,
+    file: File,
+    started: Instant,
+}
+
+impl CastRecorder {
+    fn create(path: PathBuf, cols: u16, rows: u16) -> Result<Self, String> {
+        let mut file = File::create(&path).map_err(|e| format!("Failed to create recording: {e}"))?;
+        let header = serde_json::json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": Utc::now().timestamp(),
+            "env": { "TERM": "xterm-256color" },
+        });
+        writeln!(file, "{header}").map_err(|e| format!("Failed to write recording: {e}"))?;
+        Ok(CastRecorder { path, file, started: Instant::now() })
+    }
+
+    fn event(&mut self, code: &str, data: &str) {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let _ = writeln!(self.file, "{}", serde_json::json!([elapsed, code, data]));
+    }
+}
+
+// ── Exec shell command ────────────────────────────────────────────────────────
+
+/// Wraps `s` in single quotes for `sh -c`, escaping embedded quotes as `'\''`.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Rejects env override names that aren't valid shell identifiers, since
+/// they're spliced into `export NAME=...` unquoted, and values with a NUL
+/// byte, which can't be passed in an argv.
+fn validate_exec_env(env: Option<&HashMap<String, String>>) -> Result<(), String> {
+    for (key, value) in env.into_iter().flatten() {
+        let valid = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!("Invalid environment variable name '{key}'"));
+        }
+        if value.contains('\0') {
+            return Err(format!("Invalid value for environment variable '{key}': contains NUL"));
+        }
+    }
+    Ok(())
+}
+
+/// Builds the command run inside the pod: plain `shell`, or — when a
+/// working directory or env overrides are given — `shell -c` that cd's,
+/// exports and then `exec`s an interactive `shell`. kubectl exec supports
+/// neither directly.
+pub(crate) fn exec_shell_command(
+    workdir: Option<&str>,
+    env: Option<&HashMap<String, String>>,
+    shell: &str,
+) -> Result<Vec<String>, String> {
+    let mut steps: Vec<String> = Vec::new();
+
+    if let Some(dir) = workdir.filter(|d| !d.is_empty()) {
+        steps.push(format!("cd {}", shell_quote(dir)));
+    }
+
+    validate_exec_env(env)?;
+    let mut vars: Vec<(&String, &String)> = env.into_iter().flatten().collect();
+    vars.sort();
+    for (key, value) in vars {
+        steps.push(format!("export {key}={}", shell_quote(value)));
+    }
+
+    if steps.is_empty() {
+        return Ok(vec![shell.to_string()]);
+    }
+
+    steps.push(format!("exec {}", shell_quote(shell)));
+    Ok(vec![shell.to_string(), "-c".to_string(), steps.join(" && ")])
+}
+
+/// Annotation naming the container kubectl exec picks when `-c` is omitted.
+const DEFAULT_CONTAINER_ANNOTATION: &str = "kubectl.kubernetes.io/default-container";
+
+/// The shell to exec into `pod` with: the `shells` match for the image of
+/// the container kubectl exec targets (the default-container annotation,
+/// else the first container). Images only referenced by digest still match
+/// substrings of the spec's image string.
+fn exec_shell_for_pod(pod: &Pod, shells: &BTreeMap<String, String>) -> String {
+    let Some(spec) = pod.spec.as_ref() else {
+        return DEFAULT_EXEC_SHELL.to_string();
+    };
+    let default = pod
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|a| a.get(DEFAULT_CONTAINER_ANNOTATION));
+    let container = default
+        .and_then(|name| spec.containers.iter().find(|c| &c.name == name))
+        .or_else(|| spec.containers.first());
+    let image = container.and_then(|c| c.image.as_deref()).unwrap_or_default();
+    exec_shell_for_image(shells, image)
+}
+
+/// Id of the next exec session, matched against `PtyKiller` when it ends.
+static NEXT_PTY_SESSION: AtomicU64 = AtomicU64::new(1);
+
+/// Decodes the complete UTF-8 prefix of `pending`, leaving a trailing
+/// incomplete sequence in place for the next read. A character split across
+/// two PTY reads is thus emitted whole instead of as two U+FFFD; bytes that
+/// are invalid outright still become U+FFFD.
+pub(crate) fn take_complete_utf8(pending: &mut Vec<u8>) -> String {
+    let mut out = String::new();
+    loop {
+        match std::str::from_utf8(pending) {
+            Ok(s) => {
+                out.push_str(s);
+                pending.clear();
+                return out;
+            }
+            Err(e) => {
+                let valid = e.valid_up_to();
+                out.push_str(std::str::from_utf8(&pending[..valid]).unwrap_or_default());
+                match e.error_len() {
+                    None => {
+                        pending.drain(..valid);
+                        return out;
+                    }
+                    Some(len) => {
+                        out.push(char::REPLACEMENT_CHARACTER);
+                        pending.drain(..valid + len);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Opens a PTY, spawns `kubectl exec -it` inside it, and streams raw PTY output
+/// to the frontend via `exec-output` events.  The frontend's xterm.js terminal
+/// writes bytes directly to the PTY master via the `send_exec_input` command,
+/// giving a fully interactive shell session inside the app.
+///
+/// Events emitted:
+///   `exec-output` — payload: ExecOutput — raw PTY bytes (ANSI sequences included)
+///   `exec-done`   — payload: null       — session ended
+///
+/// Each `exec-output` carries a sequence number (0, 1, 2, … per session) and
+/// the byte offset of its data in the session's output, so the frontend can
+/// detect a lost or reordered chunk. Reads use the `ptyReadBuffer` setting;
+/// bursts of output are coalesced into fewer events (see `forward_pty_output`).
+///
+/// `workdir` and `env` start the shell in that directory with those variables
+/// exported (see `exec_shell_command`). The shell comes from the `execShells`
+/// setting matched against the container's image, else `/bin/sh`.
+///
+/// With `record`, the output stream is also written to an asciinema v2 cast
+/// under `<app data>/recordings/`; `stop_recording` returns its path.
+/// Starting a new session ends any previous session and recording.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn exec_into_pod(
+    app: AppHandle,
+    name: String,
+    namespace: String,
+    source_file: String,
+    context_name: String,
+    workdir: Option<String>,
+    env: Option<HashMap<String, String>>,
+    record: Option<bool>,
+    state: State<'_, crate::PtyState>,
+    master_state: State<'_, crate::PtyMaster>,
+    killer: State<'_, crate::PtyKiller>,
+    recording: State<'_, crate::PtyRecording>,
+    settings: State<'_, AppSettings>,
+) -> Result<(), String> {
+    ensure_writable(&settings, "Exec into pods")?;
+    validate_exec_env(env.as_ref())?;
+    // Resolved before anything below touches the running session, so a
+    // failed pod lookup leaves it (and any recording) alone.
+    let shells = settings.0.lock().map_err(|e| e.to_string())?.exec_shells.clone();
+    let shell = if shells.is_empty() {
+        DEFAULT_EXEC_SHELL.to_string()
+    } else {
+        let client = build_context_client(&source_file, &context_name).await?;
+        let pod = Api::<Pod>::namespaced(client, &namespace)
+            .get(&name)
+            .await
+            .map_err(|e| e.to_string())?;
+        exec_shell_for_pod(&pod, &shells)
+    };
+
+    let args = kubectl_args("exec", &KubectlParams {
+        name: name.clone(),
+        namespace: Some(namespace.clone()),
+        source_file,
+        context_name,
+        workdir,
+        env,
+        shell: Some(shell),
+        ..Default::default()
+    })?;
+
+    // Clear previous PTY
+    {
+        let mut guard = state.0.lock().map_err(|e| e.to_string())?;
+        *guard = None;
+    }
+    {
+        let mut guard = master_state.0.lock().map_err(|e| e.to_string())?;
+        *guard = None;
+    }
+
+    let recorder = if record.unwrap_or(false) {
+        let dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("recordings");
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create recordings dir: {e}"))?;
+        let file = format!("{name}-{}.cast", Utc::now().format("%Y%m%d-%H%M%S"));
+        Some(CastRecorder::create(dir.join(file), DEFAULT_PTY_COLS, DEFAULT_PTY_ROWS)?)
+    } else {
+        None
+    };
+    *recording.0.lock().map_err(|e| e.to_string())? = recorder;
+
+    let kubectl = kubectl_binary();
+    let global = global_args(&settings)?;
+
+    let (writer, reader, child, slave, master) = tokio::task::spawn_blocking(move || {
+        let pty_system = portable_pty::native_pty_system();
+        let pty_pair = pty_system.openpty(portable_pty::PtySize {
+            rows: DEFAULT_PTY_ROWS, cols: DEFAULT_PTY_COLS,
+            pixel_width: 0, pixel_height: 0,
+        }).map_err(|e| e.to_string())?;
+
+        let mut cmd = portable_pty::CommandBuilder::new(&kubectl);
+        cmd.args(&global);
+        cmd.args(&args);
+        cmd.env("TERM", "xterm-256color");
+
+        let child = pty_pair.slave.spawn_command(cmd)
+            .map_err(|e| format!("spawn failed: {e}"))?;
+
+        let reader = pty_pair.master.try_clone_reader()
+            .map_err(|e| e.to_string())?;
+        let writer = pty_pair.master.take_writer()
+            .map_err(|e| e.to_string())?;
+
+        // Move slave out so it can be kept alive in the reader closure.
+        let slave = pty_pair.slave;
+
+        Ok::<_, String>((writer, reader, child, slave, pty_pair.master))
+    }).await.map_err(|e| e.to_string())??;
+
+    // Store the writer so send_exec_input can forward keystrokes, and the
+    // master so resize_pty can resize the terminal.
+    {
+        let mut guard = state.0.lock().map_err(|e| e.to_string())?;
+        *guard = Some(writer);
+    }
+    {
+        let mut guard = master_state.0.lock().map_err(|e| e.to_string())?;
+        *guard = Some(master);
+    }
+    let session = NEXT_PTY_SESSION.fetch_add(1, Ordering::Relaxed);
+    {
+        let mut guard = killer.0.lock().map_err(|e| e.to_string())?;
+        if let Some((_, mut previous)) = guard.replace((session, child.clone_killer())) {
+            let _ = previous.kill();
+        }
+    }
+
+    // Read PTY output in background.
+    // child and slave are moved into this closure so they stay alive for the
+    // entire session — dropping either would kill the process or close the PTY.
+    let app_clone = app.clone();
+    let app_reader = app.clone();
+    let recording = recording.0.clone();
+    let buffer_size = settings.0.lock().map_err(|e| e.to_string())?.pty_read_buffer();
+    let (tx, rx) = std::sync::mpsc::sync_channel::<Vec<u8>>(PTY_QUEUE_CHUNKS);
+    tokio::task::spawn_blocking(move || {
+        let _child = child;   // keeps kubectl exec process alive
+        let _slave = slave;   // keeps PTY slave fd open
+        let mut reader = reader;
+        let mut buf = vec![0u8; buffer_size];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    // The emitter has given up; stop reading.
+                    if tx.send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+        // Ended by itself (or was killed): forget the killer unless a newer
+        // session already replaced it, so a context switch doesn't report it.
+        if let Ok(mut guard) = app_reader.state::<crate::PtyKiller>().0.lock() {
+            if guard.as_ref().is_some_and(|(id, _)| *id == session) {
+                *guard = None;
+            }
+        }
+    });
+    tokio::task::spawn_blocking(move || {
+        forward_pty_output(rx, &recording, |out| app_clone.emit("exec-output", out).is_ok());
+        let _ = app_clone.emit("exec-done", ());
+    });
+
+    Ok(())
+}
+
+/// Forwards raw keystroke bytes from xterm.js to the PTY master writer.
+/// Called on every xterm.js `onData` event.
+#[tauri::command]
+pub async fn send_exec_input(
+    input: String,
+    state: State<'_, crate::PtyState>,
+) -> Result<(), String> {
+    let mut guard = state.0.lock().map_err(|e| e.to_string())?;
+    if let Some(ref mut writer) = *guard {
+        writer
+            .write_all(input.as_bytes())
+            .map_err(|e| format!("PTY write error: {e}"))?;
+        writer
+            .flush()
+            .map_err(|e| format!("PTY flush error: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Resizes the exec session's PTY to match the xterm.js viewport and records
+/// the new size when a recording is active.
+#[tauri::command]
+pub async fn resize_pty(
+    rows: u16,
+    cols: u16,
+    master_state: State<'_, crate::PtyMaster>,
+    recording: State<'_, crate::PtyRecording>,
+) -> Result<(), String> {
+    let guard = master_state.0.lock().map_err(|e| e.to_string())?;
+    if let Some(ref master) = *guard {
+        master
+            .resize(portable_pty::PtySize { rows, cols, pixel_width: 0, pixel_height: 0 })
+            .map_err(|e| format!("PTY resize error: {e}"))?;
+        if let Some(rec) = recording.0.lock().map_err(|e| e.to_string())?.as_mut() {
+            rec.event("r", &format!("{cols}x{rows}"));
+        }
+    }
+    Ok(())
+}
+
+/// Ends the active exec recording and returns the cast file path,
+/// or None when nothing was being recorded.
+#[tauri::command]
+pub async fn stop_recording(
+    recording: State<'_, crate::PtyRecording>,
+) -> Result<Option<String>, String> {
+    let rec = recording.0.lock().map_err(|e| e.to_string())?.take();
+    Ok(rec.map(|mut r| {
+        let _ = r.file.flush();
+        r.path.to_string_lossy().into_owned()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{
+        ContainerState, ContainerStateTerminated, ContainerStateWaiting, EnvVar,
+    };
+
+    fn pod_with(images: &[(&str, &str)], default_container: Option<&str>) -> Pod {
+        let mut pod = Pod::default();
+        pod.metadata.annotations = default_container.map(|name| {
+            BTreeMap::from([(DEFAULT_CONTAINER_ANNOTATION.to_string(), name.to_string())])
+        });
+        pod.spec = Some(PodSpec {
+            containers: images
+                .iter()
+                .map(|(name, image)| Container {
+                    name: name.to_string(),
+                    image: Some(image.to_string()),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        });
+        pod
+    }
+
+    fn pod_with_status(phase: &str, state: ContainerState, last_exit: Option<i32>) -> Pod {
+        use k8s_openapi::api::core::v1::PodStatus;
+        let status = Some(PodStatus {
+            phase: Some(phase.to_string()),
+            container_statuses: Some(vec![ContainerStatus {
+                name: "job".to_string(),
+                state: Some(state),
+                last_state: last_exit.map(|exit_code| ContainerState {
+                    terminated: Some(ContainerStateTerminated {
+                        exit_code,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        });
+        Pod { status, ..Default::default() }
+    }
+
+    fn terminated(exit_code: i32) -> ContainerState {
+        ContainerState {
+            terminated: Some(ContainerStateTerminated { exit_code, ..Default::default() }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn a_container_that_succeeded_on_retry_is_not_a_failure() {
+        let pod = pod_with_status("Succeeded", terminated(0), Some(1));
+        assert!(container_failures(&pod).is_empty());
+
+        let running = ContainerState { running: Some(Default::default()), ..Default::default() };
+        assert!(container_failures(&pod_with_status("Running", running, Some(1))).is_empty());
+    }
+
+    #[test]
+    fn reports_current_and_unrecovered_previous_failures() {
+        let failures = container_failures(&pod_with_status("Running", terminated(2), Some(1)));
+        assert_eq!((failures[0].exit_code, failures[0].previous), (2, false));
+
+        let crash_loop = ContainerState {
+            waiting: Some(ContainerStateWaiting {
+                reason: Some("CrashLoopBackOff".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let failures = container_failures(&pod_with_status("Running", crash_loop, Some(137)));
+        assert_eq!((failures[0].exit_code, failures[0].previous), (137, true));
+
+        let waiting = ContainerState { waiting: Some(Default::default()), ..Default::default() };
+        let failures = container_failures(&pod_with_status("Failed", waiting, Some(1)));
+        assert_eq!((failures[0].exit_code, failures[0].previous), (1, true));
+    }
+
+    #[test]
+    fn pty_output_is_batched_in_order_on_utf8_boundaries() {
+        let (tx, rx) = std::sync::mpsc::sync_channel(PTY_QUEUE_CHUNKS);
+        // "é" split across two queued reads, then a second batch after a pause.
+        tx.send(b"caf\xc3".to_vec()).unwrap();
+        tx.send(b"\xa9!".to_vec()).unwrap();
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(PTY_COALESCE_MAX * 2);
+            tx.send(b"next".to_vec()).unwrap();
+        });
+        let mut events = Vec::new();
+        forward_pty_output(rx, &std::sync::Mutex::new(None), |out| {
+            events.push((out.seq, out.offset, out.data));
+            true
+        });
+        sender.join().unwrap();
+        assert_eq!(events, vec![(0, 0, "café!".to_string()), (1, 6, "next".to_string())]);
+    }
+
+    #[test]
+    fn pty_output_is_abandoned_after_repeated_emit_failures() {
+        let (tx, rx) = std::sync::mpsc::sync_channel(PTY_QUEUE_CHUNKS);
+        let sender = std::thread::spawn(move || {
+            // Each chunk outlasts the widest window, so each is its own emit;
+            // sending fails once the forwarder has given up.
+            while tx.send(b"x".to_vec()).is_ok() {
+                std::thread::sleep(PTY_COALESCE_MAX + PTY_COALESCE_MIN);
+            }
+        });
+        let mut attempts = 0;
+        forward_pty_output(rx, &std::sync::Mutex::new(None), |_| {
+            attempts += 1;
+            false
+        });
+        sender.join().unwrap();
+        assert_eq!(attempts, PTY_MAX_EMIT_FAILURES);
+    }
+
+    #[test]
+    fn coalesce_window_widens_when_slow_and_narrows_when_not() {
+        let slow = next_coalesce_window(PTY_COALESCE_MIN, true);
+        assert_eq!(slow, PTY_COALESCE_MIN * 2);
+        assert_eq!(next_coalesce_window(PTY_COALESCE_MAX, true), PTY_COALESCE_MAX);
+        assert_eq!(next_coalesce_window(slow, false), PTY_COALESCE_MIN);
+        assert_eq!(next_coalesce_window(PTY_COALESCE_MIN, false), PTY_COALESCE_MIN);
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("1h30m"), Ok(chrono::Duration::minutes(90)));
+        assert_eq!(parse_duration("7d"), Ok(chrono::Duration::days(7)));
+        assert_eq!(parse_duration(" 90s "), Ok(chrono::Duration::seconds(90)));
+    }
+
+    #[test]
+    fn rejects_malformed_and_overflowing_durations() {
+        let too_long = format!("{}s", i64::MAX / 1000 + 1);
+        for s in ["90", "d", "", "0m", "5w", "1h30", "99999999999999999999s", &too_long] {
+            assert!(parse_duration(s).is_err(), "{s:?} should be invalid");
+        }
+        assert!(parse_duration(&format!("{}d", i64::MAX / 86_400 / 1000)).is_ok());
+        assert!(parse_duration("106751991167d1d").is_err());
+        // Fits in a Duration, but not as an offset from now.
+        let age = parse_duration("100000000d").unwrap();
+        assert!(Utc::now().checked_sub_signed(age).is_none());
+    }
+
+    fn spec_with(image: &str, env: &[(&str, &str)]) -> PodSpec {
+        let mut spec = pod_with(&[("app", image)], None).spec.unwrap();
+        spec.containers[0].env = Some(
+            env.iter()
+                .map(|(name, value)| EnvVar {
+                    name: name.to_string(),
+                    value: Some(value.to_string()),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+        spec
+    }
+
+    fn changes(diff: &[String]) -> Vec<&str> {
+        diff.iter().map(String::as_str).filter(|l| !l.starts_with("  ")).collect()
+    }
+
+    #[test]
+    fn identical_specs_have_no_drift() {
+        let lines = drift_lines(&spec_with("nginx:1.25", &[("A", "1")]));
+        assert_eq!(lines, ["container/app image: nginx:1.25", "container/app env A=1"]);
+        assert!(changes(&line_diff(&lines, &lines.clone())).is_empty());
+    }
+
+    #[test]
+    fn a_changed_image_is_one_removed_and_added_line() {
+        let old = drift_lines(&spec_with("nginx:1.25", &[("A", "1")]));
+        let new = drift_lines(&spec_with("nginx:1.27", &[("A", "1")]));
+        let diff = line_diff(&old, &new);
+        assert_eq!(
+            changes(&diff),
+            ["- container/app image: nginx:1.25", "+ container/app image: nginx:1.27"]
+        );
+        assert_eq!(diff.last().map(String::as_str), Some("  container/app env A=1"));
+    }
+
+    #[test]
+    fn env_order_is_not_drift() {
+        let old = drift_lines(&spec_with("nginx", &[("A", "1"), ("B", "2")]));
+        let new = drift_lines(&spec_with("nginx", &[("B", "2"), ("A", "1")]));
+        assert_eq!(old, new);
+    }
+
+    fn ago(now: chrono::DateTime<Utc>, mins: i64) -> chrono::DateTime<Utc> {
+        now - chrono::Duration::minutes(mins)
+    }
+
+    #[test]
+    fn a_steady_crash_loop_restarts_at_its_average_rate() {
+        let now = Utc::now();
+        // 12 restarts in the last hour, the latest two minutes ago.
+        let rate = restarts_per_hour(12, ago(now, 60), Some(ago(now, 2)), now);
+        assert!((rate - 12.5).abs() < 0.1, "{rate}");
+    }
+
+    #[test]
+    fn a_settled_container_decays_to_near_zero() {
+        let now = Utc::now();
+        let week = 7 * 24 * 60;
+        // 50 restarts, all of them six days ago.
+        let rate = restarts_per_hour(50, ago(now, week), Some(ago(now, week - 24 * 60)), now);
+        assert!(rate < 0.01, "{rate}");
+        assert_eq!(restarts_per_hour(0, ago(now, week), None, now), 0.0);
+    }
+
+    #[test]
+    fn a_missing_or_skewed_last_state_uses_the_lifetime_average() {
+        let now = Utc::now();
+        let rate = restarts_per_hour(4, ago(now, 120), None, now);
+        assert!((rate - 2.0).abs() < 0.01, "{rate}");
+        // lastState stamped before the pod started.
+        let rate = restarts_per_hour(4, ago(now, 120), Some(ago(now, 130)), now);
+        assert!((rate - 2.0).abs() < 0.01, "{rate}");
+    }
+
+    #[test]
+    fn restart_intervals_are_floored_at_the_minimum_backoff() {
+        let now = Utc::now();
+        let started = now - chrono::Duration::seconds(5);
+        let rate = restarts_per_hour(10, started, Some(now), now);
+        assert_eq!(rate, 3600.0 / CRASH_LOOP_MIN_BACKOFF_SECS);
+    }
+
+    fn named_pod(namespace: &str, name: &str) -> Pod {
+        let mut pod = Pod::default();
+        pod.metadata.namespace = Some(namespace.to_string());
+        pod.metadata.name = Some(name.to_string());
+        pod
+    }
+
+    #[tokio::test]
+    async fn pod_list_and_metrics_run_concurrently() {
+        // Each side waits for the other, so running them one after the other
+        // would never finish.
+        let barrier = tokio::sync::Barrier::new(2);
+        let pods = async {
+            barrier.wait().await;
+            Ok(vec![named_pod("default", "web"), named_pod("default", "db")])
+        };
+        let usage = async {
+            barrier.wait().await;
+            Some(PodUsage::from([(("default".into(), "web".into()), (250, 64 * 1024 * 1024))]))
+        };
+        let list = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            join_pods_with_metrics(pods, usage, None, &[]),
+        )
+        .await
+        .expect("pod list and metrics were awaited sequentially")
+        .unwrap();
+
+        assert!(list.metrics_available);
+        assert_eq!((list.pods[0].cpu.as_str(), list.pods[0].memory.as_str()), ("250m", "64Mi"));
+        assert_eq!(list.pods[1].cpu, pod_to_summary(named_pod("default", "db"), &[]).cpu);
+    }
+
+    #[tokio::test]
+    async fn metrics_failure_keeps_the_pod_list() {
+        let pods = async { Ok(vec![named_pod("default", "web")]) };
+        let list = join_pods_with_metrics(pods, async { None }, None, &[]).await.unwrap();
+        assert_eq!(list.pods.len(), 1);
+        assert!(!list.metrics_available);
+
+        let failed = async { Err::<Vec<Pod>, _>("forbidden".to_string()) };
+        let usage = async { Some(PodUsage::new()) };
+        let err = join_pods_with_metrics(failed, usage, None, &[]).await.unwrap_err();
+        assert_eq!(err, "forbidden");
+    }
+
+    fn api_resource(name: &str, short: &[&str], api_version: &str, kind: &str) -> ApiResourceInfo {
+        ApiResourceInfo {
+            name: name.to_string(),
+            shortnames: short.iter().map(|s| s.to_string()).collect(),
+            api_version: api_version.to_string(),
+            namespaced: true,
+            kind: kind.to_string(),
+            verbs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn resource_names_prefer_the_core_group() {
+        let resources = [
+            api_resource("deployments", &["deploy"], "apps/v1", "Deployment"),
+            api_resource("events", &["ev"], "events.k8s.io/v1", "Event"),
+            api_resource("events", &["ev"], "v1", "Event"),
+        ];
+        let picked = |kind| pick_api_resource(&resources, kind).map(|r| r.api_version.as_str());
+
+        assert_eq!(picked("events"), Some("v1"));
+        assert_eq!(picked("Event"), Some("v1"));
+        assert_eq!(picked("events.events.k8s.io"), Some("events.k8s.io/v1"));
+        assert_eq!(picked("deploy"), Some("apps/v1"));
+        assert_eq!(picked("deployments.apps"), Some("apps/v1"));
+        assert_eq!(picked("deployments.batch"), None);
+    }
+
+    #[test]
+    fn exec_shell_follows_the_container_kubectl_targets() {
+        let shells = BTreeMap::from([
+            ("distroless".to_string(), "/busybox/sh".to_string()),
+            ("alpine".to_string(), "/bin/ash".to_string()),
+        ]);
+        let images = [("app", "gcr.io/distroless/base"), ("side", "alpine:3")];
+        let pod = pod_with(&images, None);
+        assert_eq!(exec_shell_for_pod(&pod, &shells), "/busybox/sh");
+
+        let pod = pod_with(&images, Some("side"));
+        assert_eq!(exec_shell_for_pod(&pod, &shells), "/bin/ash");
+
+        let pod = pod_with(&[("app", "nginx:1.25")], Some("missing"));
+        assert_eq!(exec_shell_for_pod(&pod, &shells), DEFAULT_EXEC_SHELL);
+        assert_eq!(exec_shell_for_pod(&Pod::default(), &shells), DEFAULT_EXEC_SHELL);
+    }
+
+    #[test]
+    fn exec_shell_prefers_the_longest_image_match() {
+        let shells = BTreeMap::from([
+            ("distroless".to_string(), "/busybox/sh".to_string()),
+            ("distroless/python".to_string(), "/usr/bin/python3".to_string()),
+        ]);
+        assert_eq!(exec_shell_for_image(&shells, "gcr.io/distroless/python3"), "/usr/bin/python3");
+        assert_eq!(exec_shell_for_image(&shells, "gcr.io/distroless/static"), "/busybox/sh");
     }
-    Ok(())
 }
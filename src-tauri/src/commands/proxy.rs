@@ -1,18 +1,151 @@
-use std::process::{Command, Stdio};
-use tauri::State;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use tauri::{AppHandle, Emitter, State};
 use tokio::time::{sleep, Duration};
 
-use crate::KubectlProxy;
+use crate::commands::kubectl::{global_args, resolve_kubectl};
+use crate::models::k8s::{ProxyDiagnostics, ProxyExited, ProxyRestart, ProxyStatus};
+use crate::{AppSettings, KubectlProxy, ProxyHealth, ProxyLogs, ProxyRestarts, ProxyStartCancel};
+
+/// Local port `kubectl proxy` listens on.
+const PROXY_PORT: u16 = 8001;
+
+/// Maximum number of proxy output lines kept in `ProxyLogs`.
+const MAX_PROXY_LOG_LINES: usize = 1000;
+
+/// Maximum number of proxy (re)starts kept in `ProxyRestarts`.
+const MAX_PROXY_RESTARTS: usize = 20;
+
+/// Proxy output lines included in `get_proxy_diagnostics`.
+const DIAGNOSTICS_OUTPUT_LINES: usize = 50;
+
+/// How long `start_kubectl_proxy` waits for the proxy to answer before giving up.
+const PROXY_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay between readiness probes.
+const PROXY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often the exit watcher checks the proxy child.
+const PROXY_EXIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Proxy output lines included in `proxy-exited`.
+const EXIT_OUTPUT_LINES: usize = 20;
+
+/// Last `n` buffered proxy output lines joined for inclusion in errors.
+pub(crate) fn recent_proxy_output(logs: &Mutex<VecDeque<String>>, n: usize) -> String {
+    logs.lock()
+        .map(|buf| {
+            let skip = buf.len().saturating_sub(n);
+            buf.iter().skip(skip).cloned().collect::<Vec<_>>().join("\n")
+        })
+        .unwrap_or_default()
+}
+
+/// Reads `reader` line-by-line on a background thread, appending each line to
+/// the shared log buffer and emitting it as a `proxy-log-line` event.
+/// The thread exits when the pipe closes (i.e. the proxy process dies).
+fn forward_proxy_output<R: Read + Send + 'static>(
+    reader: R,
+    app: AppHandle,
+    logs: Arc<Mutex<VecDeque<String>>>,
+) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            push_proxy_log(&app, &logs, line);
+        }
+    });
+}
+
+/// Appends one line to the proxy log buffer and emits it as `proxy-log-line`.
+fn push_proxy_log(app: &AppHandle, logs: &Mutex<VecDeque<String>>, line: String) {
+    if let Ok(mut buf) = logs.lock() {
+        if buf.len() >= MAX_PROXY_LOG_LINES {
+            buf.pop_front();
+        }
+        buf.push_back(line.clone());
+    }
+    let _ = app.emit("proxy-log-line", line);
+}
 
+/// Watches the proxy child with process id `pid` on a background thread and
+/// emits `proxy-exited` when it dies on its own. The child stays in the shared
+/// slot (so `proxy_status` keeps working), hence `try_wait` polling rather
+/// than a blocking `wait`. The thread ends silently once the slot no longer
+/// holds `pid` — stopped by `stop_kubectl_proxy` or replaced by a restart —
+/// so planned stops never look like crashes.
+fn watch_proxy_exit(
+    pid: u32,
+    app: AppHandle,
+    proxy: Arc<Mutex<Option<Child>>>,
+    logs: Arc<Mutex<VecDeque<String>>>,
+) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(PROXY_EXIT_POLL_INTERVAL);
+        let status = {
+            let Ok(mut guard) = proxy.lock() else { return };
+            match guard.as_mut().filter(|child| child.id() == pid) {
+                None => return,
+                Some(child) => match child.try_wait() {
+                    Ok(None) => continue,
+                    Ok(Some(status)) => status,
+                    Err(_) => return,
+                },
+            }
+        };
+        // Give the output threads a moment to drain the closed pipes.
+        std::thread::sleep(PROXY_POLL_INTERVAL);
+        let recent_output = recent_proxy_output(&logs, EXIT_OUTPUT_LINES)
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let _ = app.emit(
+            "proxy-exited",
+            ProxyExited { exit_code: status.code(), status: status.to_string(), recent_output },
+        );
+        return;
+    });
+}
+
+/// Spawns `kubectl proxy` on :8001 for the given kubeconfig file and context,
+/// replacing any proxy that is already running.
+///
+/// `insecure_skip_tls` adds `--insecure-skip-tls-verify` for API servers whose
+/// CA kubectl doesn't trust; a warning is written to the proxy log when set.
+///
+/// Events emitted:
+/// - `proxy-log-line` — payload: `String` — one line of proxy stdout/stderr
+/// - `proxy-exited`   — payload: `ProxyExited` — the proxy died without being
+///   stopped or replaced (see `watch_proxy_exit`)
+///
+/// While waiting for the proxy to become ready, `cancel_proxy_start` kills it
+/// and makes this return "Proxy start cancelled".
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn start_kubectl_proxy(
+    app: AppHandle,
     context_name: Option<String>,
     source_file: Option<String>,
+    insecure_skip_tls: bool,
     state: State<'_, KubectlProxy>,
+    logs: State<'_, ProxyLogs>,
+    health: State<'_, ProxyHealth>,
+    restarts: State<'_, ProxyRestarts>,
+    settings: State<'_, AppSettings>,
+    cancel: State<'_, ProxyStartCancel>,
 ) -> Result<(), String> {
+    // Registered before spawning so a cancel that lands mid-spawn isn't lost.
+    let cancelled = cancel.0.notified();
+    tokio::pin!(cancelled);
+    cancelled.as_mut().enable();
+
+    let global = global_args(&settings)?;
     let mut args = vec![
         "proxy".to_string(),
-        "--port=8001".to_string(),
+        format!("--port={PROXY_PORT}"),
         "--append-server-path".to_string(),
         "--disable-filter=true".to_string(),
     ];
@@ -23,66 +156,242 @@ pub async fn start_kubectl_proxy(
     if let Some(ref ctx) = context_name {
         args.push(format!("--context={ctx}"));
     }
+    if insecure_skip_tls {
+        args.push("--insecure-skip-tls-verify".to_string());
+    }
 
     // ── resolve kubectl binary ────────────────────────────────────────────────
-    let kubectl_path = which::which("kubectl")
+    let kubectl_path = resolve_kubectl()
         .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| {
-            if cfg!(windows) {
-                let candidates = vec![
-                    format!(
-                        "{}\\development_tools\\kubectl.exe",
-                        std::env::var("USERPROFILE").unwrap_or_default()
-                    ),
-                    "C:\\Program Files\\Docker\\Docker\\resources\\bin\\kubectl.exe".to_string(),
-                    "kubectl.exe".to_string(),
-                ];
-                candidates
-                    .into_iter()
-                    .find(|p| std::path::Path::new(p).exists())
-                    .unwrap_or_else(|| "kubectl.exe".to_string())
-            } else {
-                "kubectl".to_string()
-            }
-        });
-
-    if !std::path::Path::new(&kubectl_path).exists() && which::which("kubectl").is_err() {
-        return Err(
-            "kubectl not found. Please install kubectl and ensure it is in your PATH."
-                .to_string(),
-        );
-    }
+        .ok_or_else(|| {
+            "kubectl not found. Please install kubectl and ensure it is in your PATH.".to_string()
+        })?;
 
     // ── spawn; drop the MutexGuard before any .await ─────────────────────────
-    {
+    let pid = {
         let mut guard = state.0.lock().map_err(|e| e.to_string())?;
 
+        let reason = match guard.as_mut().map(|child| child.try_wait()) {
+            None => "started".to_string(),
+            Some(Ok(Some(status))) => format!("previous proxy had exited ({status})"),
+            Some(_) => "replaced running proxy".to_string(),
+        };
         if let Some(mut child) = guard.take() {
             let _ = child.kill();
+            let _ = child.wait();
         }
 
-        let child = Command::new(&kubectl_path)
+        let mut child = Command::new(&kubectl_path)
+            .args(&global)
             .args(&args)
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
             .spawn()
             .map_err(|e| format!("failed to spawn kubectl proxy: {e}"))?;
 
+        if insecure_skip_tls {
+            push_proxy_log(
+                &app,
+                &logs.0,
+                "WARNING: TLS verification to the API server is disabled (--insecure-skip-tls-verify)"
+                    .to_string(),
+            );
+        }
+        if let Some(stdout) = child.stdout.take() {
+            forward_proxy_output(stdout, app.clone(), logs.0.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_proxy_output(stderr, app.clone(), logs.0.clone());
+        }
+
+        let pid = child.id();
+        watch_proxy_exit(pid, app.clone(), state.0.clone(), logs.0.clone());
         *guard = Some(child);
-    } // MutexGuard dropped here — safe to .await below
 
-    // Wait for the proxy to start listening.
-    sleep(Duration::from_millis(2000)).await;
+        let mut restarts = restarts.0.lock().map_err(|e| e.to_string())?;
+        if restarts.len() >= MAX_PROXY_RESTARTS {
+            restarts.pop_front();
+        }
+        restarts.push_back(ProxyRestart { at: Utc::now(), reason });
+        pid
+    }; // MutexGuards dropped here — safe to .await below
 
+    tokio::select! {
+        ready = wait_for_proxy(&state, &logs) => ready?,
+        _ = cancelled => {
+            // Only kill our own child — a newer start may already own the slot.
+            let mut guard = state.0.lock().map_err(|e| e.to_string())?;
+            if guard.as_ref().is_some_and(|child| child.id() == pid) {
+                if let Some(mut child) = guard.take() {
+                    let _ = child.kill();
+                    // Reap it so no zombie `kubectl proxy` is left behind.
+                    let _ = child.wait();
+                }
+            }
+            return Err("Proxy start cancelled".to_string());
+        }
+    }
+    mark_healthy(&health);
     Ok(())
 }
 
+/// Aborts a `start_kubectl_proxy` that is still waiting for readiness. Does
+/// nothing when no start is in progress; a running proxy is left alone.
+#[tauri::command]
+pub async fn cancel_proxy_start(cancel: State<'_, ProxyStartCancel>) -> Result<(), String> {
+    cancel.0.notify_waiters();
+    Ok(())
+}
+
+/// Records now as the last time the proxy answered.
+fn mark_healthy(health: &ProxyHealth) {
+    if let Ok(mut last) = health.0.lock() {
+        *last = Some(Utc::now());
+    }
+}
+
+/// Client for loopback requests to the proxy.
+/// no_proxy: HTTP(S)_PROXY must never intercept loopback traffic.
+fn loopback_client(timeout: Duration) -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .no_proxy()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+fn proxy_api_url() -> String {
+    format!("http://127.0.0.1:{PROXY_PORT}/api")
+}
+
+/// Polls the proxy's `/api` until it answers.
+///
+/// Any HTTP response means the proxy is listening — a 403 just means the user
+/// can't list `/api`, which is fine. A 401 means the proxy is up but its
+/// credentials are rejected (typically an expired token), so that fails fast
+/// with the captured proxy output instead of looking like a dead proxy.
+async fn wait_for_proxy(state: &KubectlProxy, logs: &ProxyLogs) -> Result<(), String> {
+    let client = loopback_client(Duration::from_secs(2))?;
+
+    let started = std::time::Instant::now();
+    while started.elapsed() < PROXY_READY_TIMEOUT {
+        if let Ok(resp) = client.get(proxy_api_url()).send().await {
+            if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+                return Err(format!(
+                    "kubectl proxy is up but credentials are unauthorized (token expired?)\n{}",
+                    recent_proxy_output(&logs.0, 20)
+                ));
+            }
+            return Ok(());
+        }
+
+        // Stop early if the proxy process already died.
+        let exited = state
+            .0
+            .lock()
+            .map_err(|e| e.to_string())?
+            .as_mut()
+            .map(|child| matches!(child.try_wait(), Ok(Some(_))))
+            .unwrap_or(true);
+        if exited {
+            return Err(format!(
+                "kubectl proxy exited during startup\n{}",
+                recent_proxy_output(&logs.0, 20)
+            ));
+        }
+
+        sleep(PROXY_POLL_INTERVAL).await;
+    }
+
+    Err(format!(
+        "kubectl proxy did not become ready within {}s\n{}",
+        PROXY_READY_TIMEOUT.as_secs(),
+        recent_proxy_output(&logs.0, 20)
+    ))
+}
+
 /// Kills the running kubectl proxy process.
 #[tauri::command]
 pub async fn stop_kubectl_proxy(state: State<'_, KubectlProxy>) -> Result<(), String> {
     let mut guard = state.0.lock().map_err(|e| e.to_string())?;
     if let Some(mut child) = guard.take() {
         child.kill().map_err(|e| e.to_string())?;
+        let _ = child.wait();
     }
     Ok(())
 }
+
+/// Returns the buffered kubectl proxy output, oldest line first.
+#[tauri::command]
+pub async fn get_proxy_logs(logs: State<'_, ProxyLogs>) -> Result<Vec<String>, String> {
+    let buf = logs.0.lock().map_err(|e| e.to_string())?;
+    Ok(buf.iter().cloned().collect())
+}
+
+/// Cheap liveness check for the UI's "proxy connected" indicator.
+///
+/// `running` reflects whether the managed proxy child is still alive. With
+/// `ping`, a running proxy is also asked for `/api` (500 ms budget); an answer
+/// other than 401 updates `last_healthy`, which otherwise reflects the last
+/// successful startup or ping.
+#[tauri::command]
+pub async fn proxy_status(
+    ping: Option<bool>,
+    state: State<'_, KubectlProxy>,
+    health: State<'_, ProxyHealth>,
+) -> Result<ProxyStatus, String> {
+    let running = state
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_mut()
+        .is_some_and(|child| matches!(child.try_wait(), Ok(None)));
+
+    if running && ping.unwrap_or(false) {
+        let client = loopback_client(Duration::from_millis(500))?;
+        if let Ok(resp) = client.get(proxy_api_url()).send().await {
+            if resp.status() != reqwest::StatusCode::UNAUTHORIZED {
+                mark_healthy(&health);
+            }
+        }
+    }
+
+    let last_healthy = *health.0.lock().map_err(|e| e.to_string())?;
+    Ok(ProxyStatus {
+        running,
+        port: running.then_some(PROXY_PORT),
+        last_healthy: last_healthy.map(|t| t.to_rfc3339()),
+    })
+}
+
+/// Proxy stability report: recent (re)starts with reasons, uptime of the
+/// running proxy, and the tail of its output. Several restarts in a short
+/// span usually mean the proxy is flapping on bad credentials.
+#[tauri::command]
+pub async fn get_proxy_diagnostics(
+    state: State<'_, KubectlProxy>,
+    logs: State<'_, ProxyLogs>,
+    restarts: State<'_, ProxyRestarts>,
+) -> Result<ProxyDiagnostics, String> {
+    let running = state
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_mut()
+        .is_some_and(|child| matches!(child.try_wait(), Ok(None)));
+
+    let restarts: Vec<ProxyRestart> =
+        restarts.0.lock().map_err(|e| e.to_string())?.iter().cloned().collect();
+
+    let uptime_secs = restarts
+        .last()
+        .filter(|_| running)
+        .map(|last| (Utc::now() - last.at).num_seconds().max(0) as u64);
+
+    let recent_output = recent_proxy_output(&logs.0, DIAGNOSTICS_OUTPUT_LINES)
+        .lines()
+        .map(str::to_string)
+        .collect();
+
+    Ok(ProxyDiagnostics { restarts, uptime_secs, recent_output })
+}
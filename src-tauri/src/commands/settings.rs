@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+use tokio::sync::Semaphore;
+
+use crate::settings::Settings;
+use crate::{AppSettings, RequestLimiter};
+
+// ── read-only mode ────────────────────────────────────────────────────────────
+
+/// Fails with a "ReadOnly:" error when read-only mode is on (see
+/// `Settings::read_only`). Mutating commands call this before touching the
+/// cluster or any kubeconfig file.
+pub(crate) fn ensure_writable(settings: &AppSettings, action: &str) -> Result<(), String> {
+    if settings.0.lock().map_err(|e| e.to_string())?.read_only() {
+        return Err(format!("ReadOnly: {action} is disabled in read-only mode"));
+    }
+    Ok(())
+}
+
+/// Whether mutating commands are currently blocked, so the UI can grey
+/// them out. Reflects `CLUSTEROPS_READONLY` as well as the setting.
+#[tauri::command]
+pub async fn is_read_only(settings: State<'_, AppSettings>) -> Result<bool, String> {
+    Ok(settings.0.lock().map_err(|e| e.to_string())?.read_only())
+}
+
+// ── request limiter ───────────────────────────────────────────────────────────
+
+/// The shared semaphore bulk commands take a permit from before each API
+/// request, so at most `maxConcurrentRequests` are in flight across them.
+pub(crate) fn request_limiter(limiter: &RequestLimiter) -> Result<Arc<Semaphore>, String> {
+    Ok(limiter.0.lock().map_err(|e| e.to_string())?.clone())
+}
+
+// ── get_settings / update_settings ────────────────────────────────────────────
+
+/// Returns every persisted preference (camelCase keys, see `settings.rs`).
+#[tauri::command]
+pub async fn get_settings(settings: State<'_, AppSettings>) -> Result<Settings, String> {
+    Ok(settings.0.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Merges `patch` into the settings, saves them and returns the result.
+/// Only the keys present in `patch` change; `null` resets a key to its default.
+#[tauri::command]
+pub async fn update_settings(
+    app: AppHandle,
+    patch: serde_json::Value,
+    settings: State<'_, AppSettings>,
+    limiter: State<'_, RequestLimiter>,
+) -> Result<Settings, String> {
+    let mut guard = settings.0.lock().map_err(|e| e.to_string())?;
+    let updated = guard.patched(patch)?;
+    updated.save(&app)?;
+    if updated.max_concurrent_requests() != guard.max_concurrent_requests() {
+        let limit = updated.max_concurrent_requests();
+        *limiter.0.lock().map_err(|e| e.to_string())? = Arc::new(Semaphore::new(limit));
+    }
+    *guard = updated.clone();
+    Ok(updated)
+}
@@ -1,9 +1,12 @@
 pub mod ai_provider;
 pub mod commands;
 pub mod models;
+pub mod settings;
 
+use std::collections::{HashMap, VecDeque};
 use std::io::Write;
 use std::process::Child;
+use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
 use tauri::Manager;
 
@@ -11,10 +14,93 @@ use tauri::Manager;
 /// Arc lets us clone out of the tauri State borrow inside the RunEvent::Exit handler.
 pub struct KubectlProxy(pub Arc<Mutex<Option<Child>>>);
 
+/// Rolling buffer of kubectl proxy stdout/stderr lines, newest last.
+/// Filled by the reader threads spawned in `start_kubectl_proxy`.
+pub struct ProxyLogs(pub Arc<Mutex<VecDeque<String>>>);
+
+/// Recent kubectl proxy (re)starts, oldest first, recorded by `start_kubectl_proxy`.
+pub struct ProxyRestarts(pub Mutex<VecDeque<models::k8s::ProxyRestart>>);
+
+/// When the kubectl proxy last answered a readiness probe or `proxy_status` ping.
+pub struct ProxyHealth(pub Mutex<Option<chrono::DateTime<chrono::Utc>>>);
+
+/// Wakes `start_kubectl_proxy` calls waiting for readiness; see `cancel_proxy_start`.
+pub struct ProxyStartCancel(pub tokio::sync::Notify);
+
+/// How `list_pods` & co. reach the API server.
+#[derive(Clone)]
+pub enum KubeClientMode {
+    /// Through `kubectl proxy` on :8001 — works with any auth, including exec plugins.
+    Proxy,
+    /// Directly via kube-rs, using the named kubeconfig file and context.
+    Direct { source_file: String, context_name: String },
+    /// Service-account credentials of the pod this app runs in.
+    InCluster,
+}
+
+/// The active client mode. Starts as `Proxy`; switched by `use_direct_client`,
+/// `use_proxy_client` and `connect_in_cluster`.
+pub struct ClientMode(pub Mutex<KubeClientMode>);
+
+/// The context `set_active_context` last switched to. In read-only mode the
+/// kubeconfig's `current-context` isn't written, so the file can lag behind
+/// the context the app is actually on.
+pub struct SwitchedContext(pub Mutex<Option<String>>);
+
+/// Set once `metrics-unavailable` has been sent for the current connection.
+/// Cleared whenever the client mode is set (`use_direct_client`,
+/// `use_proxy_client`, `connect_in_cluster`), so each cluster connected to
+/// gets its own hint.
+pub struct MetricsHintShown(pub AtomicBool);
+
+/// Persistent settings (see `settings.rs`), loaded at startup.
+pub struct AppSettings(pub Mutex<settings::Settings>);
+
+/// Permits for API requests made by bulk commands, sized by the
+/// `maxConcurrentRequests` setting. Replaced when the setting changes;
+/// requests already holding a permit of the old one finish normally.
+pub struct RequestLimiter(pub Mutex<Arc<tokio::sync::Semaphore>>);
+
+/// kubectl children of running `get_pod_logs` / `tail_pod_file` streams, keyed by stream id.
+/// Emptied by `stop_pod_logs`, newer follows of the same pod, and on exit.
+pub struct PodLogStreams(pub Mutex<HashMap<u64, commands::logs::LogFollower>>);
+
+/// kubectl children of running `stream_pod_all_container_logs` calls, so
+/// `stop_pod_log_streams` can kill them.
+pub struct LogStreams(pub Mutex<Vec<tokio::process::Child>>);
+
+/// Set by `cancel_kubeconfig_scan`; checked between files by `get_kubeconfig_contexts`.
+pub struct KubeconfigScanCancel(pub AtomicBool);
+
+/// `list_api_resources` results, keyed by (kubeconfig file, context name).
+/// Discovery is slow on clusters with many CRDs, so it runs once per context
+/// unless the caller asks for a refresh.
+pub struct ApiResourcesCache(
+    pub Mutex<HashMap<(String, String), Vec<models::k8s::ApiResourceInfo>>>,
+);
+
+/// Background task of the running `watch_namespaces`.
+pub struct NamespaceWatch(pub Mutex<Option<tokio::task::JoinHandle<()>>>);
+
+/// The `kubectl rollout status -w` child of the running `watch_rollout`.
+pub struct RolloutWatch(pub Mutex<Option<tokio::process::Child>>);
+
 /// Holds the PTY master writer so `send_exec_input` can forward keystrokes.
 /// Replaced each time a new exec session starts.
 pub struct PtyState(pub Mutex<Option<Box<dyn Write + Send>>>);
 
+/// Kills the `kubectl exec` child of the current exec session, tagged with
+/// its session id. Replaced (and the previous session killed) each time a new
+/// exec session starts; cleared when the session ends by itself.
+pub struct PtyKiller(pub Mutex<Option<(u64, Box<dyn portable_pty::ChildKiller + Send + Sync>)>>);
+
+/// Holds the PTY master of the current exec session so `resize_pty` can
+/// change the terminal size. Replaced each time a new exec session starts.
+pub struct PtyMaster(pub Mutex<Option<Box<dyn portable_pty::MasterPty + Send>>>);
+
+/// Active asciinema recording of the exec session, shared with the PTY reader thread.
+pub struct PtyRecording(pub Arc<Mutex<Option<commands::pods::CastRecorder>>>);
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -29,30 +115,128 @@ pub fn run() {
 
             // Proxy starts as None — the frontend calls start_kubectl_proxy on mount.
             app.manage(KubectlProxy(Arc::new(Mutex::new(None))));
+            app.manage(ProxyLogs(Arc::new(Mutex::new(VecDeque::new()))));
+            app.manage(ProxyHealth(Mutex::new(None)));
+            app.manage(ProxyRestarts(Mutex::new(VecDeque::new())));
+            app.manage(ProxyStartCancel(tokio::sync::Notify::new()));
+            app.manage(ClientMode(Mutex::new(KubeClientMode::Proxy)));
+            app.manage(SwitchedContext(Mutex::new(None)));
+            app.manage(MetricsHintShown(AtomicBool::new(false)));
+            let settings = settings::Settings::load(app.handle());
+            let limit = settings.max_concurrent_requests();
+            app.manage(AppSettings(Mutex::new(settings)));
+            app.manage(RequestLimiter(Mutex::new(Arc::new(tokio::sync::Semaphore::new(limit)))));
+            app.manage(PodLogStreams(Mutex::new(HashMap::new())));
+            app.manage(LogStreams(Mutex::new(Vec::new())));
+            app.manage(RolloutWatch(Mutex::new(None)));
+            app.manage(NamespaceWatch(Mutex::new(None)));
+            app.manage(ApiResourcesCache(Mutex::new(HashMap::new())));
+            app.manage(KubeconfigScanCancel(AtomicBool::new(false)));
             // PTY writer starts as None — populated when exec_into_pod is called.
             app.manage(PtyState(Mutex::new(None)));
+            app.manage(PtyMaster(Mutex::new(None)));
+            app.manage(PtyKiller(Mutex::new(None)));
+            app.manage(PtyRecording(Arc::new(Mutex::new(None))));
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::kubeconfig::get_kubeconfig_contexts,
+            commands::kubeconfig::stream_kubeconfig_contexts,
+            commands::kubeconfig::cancel_kubeconfig_scan,
+            commands::kubeconfig::list_kubeconfig_files,
+            commands::kubeconfig::add_kubeconfig_search_path,
+            commands::kubeconfig::get_kubeconfig_search_paths,
+            commands::kubeconfig::get_active_context,
             commands::kubeconfig::set_active_context,
+            commands::kubeconfig::get_kubeconfig_raw,
+            commands::kubeconfig::set_kubeconfig_raw,
+            commands::kubeconfig::find_orphaned_kubeconfig_entries,
+            commands::kubeconfig::prune_orphaned_entries,
+            commands::kubeconfig::open_kubeconfig_in_editor,
             commands::kubeconfig::check_cluster_health,
+            commands::kubeconfig::test_kubeconfig_file,
+            commands::kubeconfig::context_auth_requirements,
+            commands::kubeconfig::context_connection_details,
+            commands::kubeconfig::clusters_dashboard,
+            commands::kubeconfig::compare_namespaces,
+            commands::pods::use_direct_client,
+            commands::pods::use_proxy_client,
+            commands::pods::connect_in_cluster,
+            commands::settings::get_settings,
+            commands::settings::is_read_only,
+            commands::settings::update_settings,
+            commands::pods::set_default_namespace,
+            commands::pods::get_default_namespace,
+            commands::pods::effective_target,
             commands::pods::list_pods,
+            commands::pods::list_all_pods,
+            commands::pods::list_pods_for_context,
+            commands::pods::list_pods_on_node,
+            commands::pods::top_restarting_pods,
+            commands::pods::recent_warning_events,
             commands::pods::list_namespaces,
+            commands::pods::list_namespaces_with_phase,
+            commands::pods::namespace_pod_counts,
+            commands::pods::watch_namespaces,
+            commands::pods::stop_watch_namespaces,
+            commands::pods::set_system_namespace_prefixes,
+            commands::pods::get_pod,
+            commands::pods::find_pod_by_uid,
+            commands::pods::wait_for_pod,
+            commands::pods::get_pod_scheduling,
+            commands::pods::get_container_last_state,
+            commands::pods::get_pod_images,
+            commands::pods::get_job_logs,
+            commands::pods::list_pod_containers,
+            commands::pods::create_namespace,
+            commands::pods::delete_namespace,
+            commands::pods::namespace_contents_summary,
+            commands::pods::deployment_rollout_progress,
+            commands::pods::snapshot_cluster,
+            commands::pods::load_snapshot,
+            commands::pods::preview_delete_pod,
+            commands::pods::pod_template_drift,
+            commands::pods::container_restart_velocity,
+            commands::pods::diagnose_image_pull,
+            commands::pods::get_resource_yaml,
+            commands::pods::patch_resource,
             commands::pods::delete_pod,
+            commands::pods::evict_pod,
+            commands::pods::cleanup_finished_pods,
             commands::pods::exec_into_pod,
             commands::pods::send_exec_input,
+            commands::pods::resize_pty,
+            commands::pods::stop_recording,
             commands::kubectl::describe_pod,
+            commands::kubectl::describe_pod_stream,
+            commands::kubectl::describe_pod_structured,
             commands::kubectl::get_pod_describe_for_security,
             commands::kubectl::get_network_scan_data,
             commands::kubectl::get_rbac_scan_data,
             commands::kubectl::get_namespace_scan_data,
             commands::kubectl::get_node_scan_data,
             commands::kubectl::run_kubectl,
+            commands::kubectl::watch_rollout,
+            commands::kubectl::stop_rollout,
+            commands::kubectl::build_kubectl_command,
+            commands::kubectl::check_version_skew,
+            commands::kubectl::kubectl_top_pods,
+            commands::kubectl::kubectl_top_nodes,
+            commands::kubectl::list_api_resources,
+            commands::kubectl::check_prerequisites,
             commands::logs::get_pod_logs,
+            commands::logs::stop_pod_logs,
+            commands::logs::tail_pod_file,
+            commands::logs::stream_pod_all_container_logs,
+            commands::logs::stop_pod_log_streams,
             commands::proxy::start_kubectl_proxy,
+            commands::proxy::cancel_proxy_start,
             commands::proxy::stop_kubectl_proxy,
+            commands::proxy::get_proxy_logs,
+            commands::proxy::proxy_status,
+            commands::proxy::get_proxy_diagnostics,
+            commands::diagnostics::collect_diagnostics,
             commands::ai::analyze_with_ai,
             commands::ai::analyze_security,
             commands::ai::analyze_network_scan,
@@ -73,6 +257,22 @@ pub fn run() {
                         let _ = child.kill();
                     }
                 };
+                // Log followers are tokio children; start_kill doesn't need the runtime.
+                if let Ok(mut guard) = app_handle.state::<PodLogStreams>().0.lock() {
+                    for (_, mut follower) in guard.drain() {
+                        follower.kill();
+                    }
+                }
+                if let Ok(mut guard) = app_handle.state::<LogStreams>().0.lock() {
+                    for mut child in guard.drain(..) {
+                        let _ = child.start_kill();
+                    }
+                }
+                if let Ok(mut guard) = app_handle.state::<RolloutWatch>().0.lock() {
+                    if let Some(mut child) = guard.take() {
+                        let _ = child.start_kill();
+                    }
+                }
             }
         });
 }
@@ -27,12 +27,630 @@ pub struct KubeContext {
 pub struct PodSummary {
     pub name: String,
     pub namespace: String,
+    /// Pass as `uid` to describe / logs so they refuse a recreated namesake.
+    pub uid: String,
     pub status: String,
     pub ready: String,
     pub restarts: u32,
     pub age: String,
+    /// `metadata.creationTimestamp` as RFC3339, for an exact time beside `age`.
+    pub created_at: Option<String>,
     pub cpu: String,
     pub memory: String,
     pub node: String,
     pub labels: HashMap<String, String>,
+    /// Per-container breakdown of `ready` / `restarts`.
+    pub containers: Vec<ContainerStatusSummary>,
+}
+
+/// One container's health within a `PodSummary`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerStatusSummary {
+    pub name: String,
+    pub ready: bool,
+    pub restarts: u32,
+    /// "running", "waiting", "terminated" or "unknown".
+    pub state: String,
+    /// Waiting / terminated reason, e.g. "CrashLoopBackOff" or "OOMKilled".
+    pub reason: Option<String>,
+}
+
+/// `list_pods` result. `metrics_available` is false when metrics-server did not
+/// respond, in which case every pod's cpu / memory is "N/A".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodList {
+    pub pods: Vec<PodSummary>,
+    pub metrics_available: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerInfo {
+    pub name: String,
+    /// True for entries from `spec.initContainers`.
+    pub is_init: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthRequirements {
+    /// "token" | "exec" | "client-cert" | "oidc" | "none"
+    pub auth_type: String,
+    /// Exec credential plugin command (e.g. "aws", "gke-gcloud-auth-plugin").
+    pub exec_command: Option<String>,
+    /// Whether `exec_command` resolves on PATH (or as an absolute path).
+    pub exec_command_found: bool,
+}
+
+/// Connection settings of a context's cluster entry — see
+/// `context_connection_details`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionDetails {
+    pub server: Option<String>,
+    /// `proxy-url`; kubectl honours it, `check_cluster_health` does not.
+    pub proxy_url: Option<String>,
+    pub insecure_skip_tls: bool,
+    /// Whether the CA is embedded as `certificate-authority-data`.
+    pub has_ca_data: bool,
+    /// `certificate-authority` file path, relative paths as written.
+    pub ca_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionSkew {
+    /// kubectl gitVersion, e.g. "v1.30.2"
+    pub client: String,
+    /// API server gitVersion, e.g. "v1.28.9-eks-036c24b"
+    pub server: String,
+    /// False when client and server minor versions differ by more than one.
+    pub skew_ok: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerDetail {
+    pub name: String,
+    pub image: String,
+    /// "running" | "waiting" | "terminated" | "unknown"
+    pub state: String,
+    /// Waiting/terminated reason, e.g. "CrashLoopBackOff" or "OOMKilled".
+    pub reason: Option<String>,
+    pub ready: bool,
+    pub restart_count: u32,
+    /// Reason the previous instance terminated — why it restarted.
+    pub last_termination_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodCondition {
+    #[serde(rename = "type")]
+    pub condition_type: String,
+    pub status: String,
+    pub reason: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnerRef {
+    pub kind: String,
+    pub name: String,
+}
+
+/// Full pod details for the detail drawer — everything `PodSummary` leaves out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodDetail {
+    pub name: String,
+    pub namespace: String,
+    pub uid: String,
+    pub status: String,
+    pub node: String,
+    pub pod_ip: Option<String>,
+    /// RFC3339 timestamp of when the kubelet started the pod.
+    pub start_time: Option<String>,
+    pub qos_class: Option<String>,
+    pub containers: Vec<ContainerDetail>,
+    pub init_containers: Vec<ContainerDetail>,
+    pub conditions: Vec<PodCondition>,
+    pub owner_references: Vec<OwnerRef>,
+    pub labels: HashMap<String, String>,
+}
+
+/// A container's previous termination — see `get_container_last_state`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerTermination {
+    pub exit_code: i32,
+    /// e.g. "OOMKilled", "Error", "Completed".
+    pub reason: Option<String>,
+    pub signal: Option<i32>,
+    /// RFC3339 timestamps.
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+/// `namespace_pod_counts` result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespacePodCounts {
+    /// Namespace → number of pods.
+    pub counts: HashMap<String, usize>,
+    /// Namespace → status (as shown in `PodSummary.status`) → number of pods.
+    /// Empty unless `by_status` was requested.
+    pub by_status: HashMap<String, HashMap<String, usize>>,
+    pub total: usize,
+}
+
+/// How many objects of one kind a namespace holds — see `namespace_contents_summary`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KindCount {
+    /// Plural, lower-case, e.g. "pods", "persistentvolumeclaims".
+    pub kind: String,
+    /// None when the list failed, e.g. RBAC forbids it.
+    pub count: Option<usize>,
+    pub error: Option<String>,
+}
+
+/// Structured `kubectl rollout status` — see `deployment_rollout_progress`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloutProgress {
+    /// "pending" (spec change not yet observed), "progressing", "complete",
+    /// "paused" or "stalled" (ProgressDeadlineExceeded).
+    pub phase: String,
+    pub updated: i32,
+    pub available: i32,
+    /// Desired replicas (`spec.replicas`).
+    pub total: i32,
+    /// What the rollout is waiting for, in kubectl's wording.
+    pub message: String,
+}
+
+/// What a container asked for vs what it runs — see `get_pod_images`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerImage {
+    pub container: String,
+    /// True for entries from `spec.initContainers`.
+    pub is_init: bool,
+    /// The image reference from the spec, e.g. "nginx:latest".
+    pub image: String,
+    /// Resolved digest from `containerStatuses[].imageID`; None until the
+    /// image has been pulled.
+    pub image_id: Option<String>,
+    /// "Always", "IfNotPresent" or "Never".
+    pub pull_policy: Option<String>,
+}
+
+/// One pod of a Job — see `get_job_logs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobPodLogs {
+    pub name: String,
+    /// Pod phase, e.g. "Succeeded", "Failed", "Running".
+    pub phase: String,
+    /// Phase "Failed", or a container that exited non-zero.
+    pub failed: bool,
+    /// Failing containers with their exit code and reason.
+    pub failures: Vec<ContainerFailure>,
+    /// Logs of the failing containers, one "==> container <==" section each.
+    /// Only set for failed pods.
+    pub logs: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerFailure {
+    pub container: String,
+    pub exit_code: i32,
+    pub reason: Option<String>,
+    /// True when the failure is from `lastState` (the container was restarted
+    /// in place, e.g. `restartPolicy: OnFailure`), so logs are `--previous`.
+    pub previous: bool,
+}
+
+/// How fast one container is restarting right now — see `container_restart_velocity`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartVelocity {
+    pub container: String,
+    pub restart_count: i32,
+    /// Age of the last restart, e.g. "3m"; None if it never restarted.
+    pub last_restart: Option<String>,
+    /// Estimated current rate; decays once the container stops restarting.
+    pub restarts_per_hour: f64,
+    /// At or above the `hotRestartsPerHour` setting.
+    pub hot: bool,
+}
+
+/// Why a container can't pull its image — see `diagnose_image_pull`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImagePullDiagnosis {
+    pub container: String,
+    pub image: String,
+    /// "ImagePullBackOff" or "ErrImagePull".
+    pub reason: String,
+    /// The kubelet's message, e.g. "... pull access denied ...".
+    pub message: Option<String>,
+    /// The pod's `imagePullSecrets`, in spec order.
+    pub referenced_secrets: Vec<String>,
+    /// Referenced secrets that don't exist in the pod's namespace.
+    pub missing_secrets: Vec<String>,
+}
+
+/// Why a pod is (or isn't) placed where it is — see `get_pod_scheduling`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodScheduling {
+    /// "Guaranteed", "Burstable" or "BestEffort".
+    pub qos_class: String,
+    pub node: Option<String>,
+    pub tolerations: Vec<String>,
+    pub node_selector: HashMap<String, String>,
+    pub priority_class: Option<String>,
+    /// Scheduler message for a Pending pod that could not be placed.
+    pub unschedulable_reason: Option<String>,
+}
+
+/// Result of `delete_pod`. An owner (usually a ReplicaSet, StatefulSet,
+/// DaemonSet or Job) means the pod will most likely be recreated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletePodResult {
+    pub deleted: bool,
+    pub owner_kind: Option<String>,
+    pub owner_name: Option<String>,
+}
+
+/// `pod_template_drift` result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodTemplateDrift {
+    /// The controller whose template was compared, e.g. a ReplicaSet.
+    pub owner: OwnerRef,
+    /// True when any compared field differs from the template.
+    pub drifted: bool,
+    /// Line diff of the compared fields: "  " unchanged, "- " template only,
+    /// "+ " pod only.
+    pub diff: Vec<String>,
+}
+
+/// A finished pod found by `cleanup_finished_pods`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinishedPod {
+    pub name: String,
+    pub namespace: String,
+    /// As shown in `PodSummary.status`, e.g. "Completed", "Error".
+    pub status: String,
+    /// Time since the pod finished, formatted like `PodSummary.age`.
+    pub finished: String,
+    /// Set when deleting this pod failed; the others are still deleted.
+    pub error: Option<String>,
+}
+
+/// Read-only preview of what deleting a pod would affect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletePreview {
+    /// Top-level controller, e.g. the Deployment behind a ReplicaSet-owned pod.
+    pub owner: Option<OwnerRef>,
+    /// True when a controller will create a replacement pod.
+    pub will_be_recreated: bool,
+    /// True when a matching PodDisruptionBudget currently allows no disruptions.
+    pub eviction_blocked: bool,
+    /// Names of the PodDisruptionBudgets that would block an eviction.
+    pub blocking_pdbs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Prerequisites {
+    pub kubectl_found: bool,
+    pub kubectl_path: Option<String>,
+    /// Running inside a Kubernetes pod with a mounted service account.
+    pub in_cluster: bool,
+}
+
+/// One tile on the clusters overview screen.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterCard {
+    pub display_name: String,
+    pub context_name: String,
+    pub source_file: String,
+    /// "healthy" | "slow" | "unreachable" | "unknown"
+    pub health: String,
+    pub latency_ms: Option<u64>,
+    pub server_version: Option<String>,
+    pub pod_count: Option<u64>,
+}
+
+/// `pod-log-line` payload of `stream_pod_all_container_logs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerLogLine {
+    pub container: String,
+    pub line: String,
+}
+
+/// One top-level section of `kubectl describe` output, e.g. "Containers".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DescribeSection {
+    pub name: String,
+    pub content: String,
+}
+
+/// Result of `proxy_status`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyStatus {
+    pub running: bool,
+    /// Local port, when running.
+    pub port: Option<u16>,
+    /// RFC3339 timestamp of the last successful probe.
+    pub last_healthy: Option<String>,
+}
+
+/// Payload of "kubeconfig-scan-progress" — see `get_kubeconfig_contexts`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KubeconfigScanProgress {
+    /// Directory of the file just read.
+    pub dir: String,
+    pub files_done: usize,
+    pub files_total: usize,
+}
+
+/// One kubectl proxy (re)start recorded by `start_kubectl_proxy`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyRestart {
+    pub at: chrono::DateTime<chrono::Utc>,
+    /// "started", "replaced running proxy" or "previous proxy had exited (...)".
+    pub reason: String,
+}
+
+/// Result of `get_proxy_diagnostics`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyDiagnostics {
+    /// Oldest first, bounded.
+    pub restarts: Vec<ProxyRestart>,
+    /// Seconds since the running proxy started; None when not running.
+    pub uptime_secs: Option<u64>,
+    /// Tail of the proxy's stdout/stderr.
+    pub recent_output: Vec<String>,
+}
+
+/// Payload of the `proxy-exited` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyExited {
+    /// None when the process was killed by a signal.
+    pub exit_code: Option<i32>,
+    /// Exit status as the OS describes it, e.g. "exit status: 1".
+    pub status: String,
+    /// Tail of the proxy's stdout/stderr.
+    pub recent_output: Vec<String>,
+}
+
+/// Kubeconfig `clusters` / `users` entries no context refers to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedKubeconfigEntries {
+    pub clusters: Vec<String>,
+    pub users: Vec<String>,
+}
+
+/// Payload of `session-invalidated`: streaming sessions stopped because the
+/// cluster they were opened against is no longer the active one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInvalidated {
+    /// e.g. "Switched from context 'prod' to 'staging'".
+    pub reason: String,
+    /// What was stopped: any of "exec", "logs", "namespace-watch", "rollout".
+    pub sessions: Vec<String>,
+}
+
+/// One row of `kubectl top pods` / `kubectl top nodes`, values as kubectl
+/// prints them (e.g. "250m", "512Mi").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopUsage {
+    pub name: String,
+    pub cpu: String,
+    pub memory: String,
+}
+
+/// One row of `kubectl api-resources -o wide`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiResourceInfo {
+    /// Plural resource name, e.g. "deployments".
+    pub name: String,
+    /// e.g. ["deploy"]; empty for most CRDs.
+    pub shortnames: Vec<String>,
+    /// "v1" for the core group, otherwise "group/version", e.g. "apps/v1".
+    pub api_version: String,
+    pub namespaced: bool,
+    pub kind: String,
+    /// e.g. ["get", "list", "watch"]; empty for resources kubectl can't query.
+    pub verbs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceInfo {
+    pub name: String,
+    /// "Active" or "Terminating".
+    pub phase: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextReachability {
+    pub name: String,
+    pub cluster: String,
+    /// A `check_cluster_health` status: "healthy", "slow", "unreachable" or "unknown".
+    pub reachable: String,
+}
+
+/// Result of `test_kubeconfig_file`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KubeconfigTest {
+    pub contexts: Vec<ContextReachability>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KubeconfigFile {
+    pub path: String,
+    pub context_count: usize,
+    pub contexts: Vec<String>,
+    pub is_valid: bool,
+    pub parse_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkloadRef {
+    /// "Deployment" or "Pod".
+    pub kind: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicaDiff {
+    pub kind: String,
+    pub name: String,
+    pub replicas_a: i32,
+    pub replicas_b: i32,
+}
+
+/// Result of `compare_namespaces` — "a" and "b" are the two contexts in the
+/// order they were passed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceDiff {
+    pub namespace: String,
+    pub only_in_a: Vec<WorkloadRef>,
+    pub only_in_b: Vec<WorkloadRef>,
+    pub replica_differences: Vec<ReplicaDiff>,
+}
+
+/// A context and the API server it points at, as listed in a `SupportBundle`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextServer {
+    pub context_name: String,
+    pub source_file: String,
+    pub server_url: Option<String>,
+}
+
+/// A frozen copy of a cluster's state — written by `snapshot_cluster`, read
+/// back by `load_snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterSnapshot {
+    /// Format version; `load_snapshot` refuses newer ones.
+    pub version: u32,
+    /// RFC3339 timestamp.
+    pub taken_at: String,
+    pub context_name: String,
+    pub source_file: String,
+    pub server_url: Option<String>,
+    /// Pods in every namespace, as `list_all_pods` returns them.
+    pub pods: PodList,
+    /// Raw Deployment objects (managedFields stripped); None unless requested.
+    pub deployments: Option<Vec<serde_json::Value>>,
+    /// Why requested deployments are missing, e.g. listing them was forbidden.
+    pub deployments_error: Option<String>,
+    /// Raw Node objects (managedFields stripped); None unless requested.
+    pub nodes: Option<Vec<serde_json::Value>>,
+    /// Why requested nodes are missing, e.g. listing them was forbidden.
+    pub nodes_error: Option<String>,
+}
+
+/// Result of `collect_diagnostics`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundle {
+    pub app_version: String,
+    /// `std::env::consts::OS` / `ARCH`, e.g. "linux" / "x86_64".
+    pub os: String,
+    pub arch: String,
+    pub wsl: bool,
+    pub in_cluster: bool,
+    /// None when kubectl could not be found.
+    pub kubectl_path: Option<String>,
+    pub kubectl_version: Option<String>,
+    pub kubeconfig_files: Vec<KubeconfigFile>,
+    pub contexts: Vec<ContextServer>,
+    pub proxy_running: bool,
+    /// RFC3339 timestamp.
+    pub proxy_last_healthy: Option<String>,
+    pub proxy_output: Vec<String>,
+}
+
+/// Payload of "exec-output" — see `exec_into_pod`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecOutput {
+    /// 0-based, incremented per event within a session.
+    pub seq: u64,
+    /// Byte offset of `data` within the session's output.
+    pub offset: u64,
+    pub data: String,
+}
+
+/// Warning events sharing one reason — see `recent_warning_events`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WarningSummary {
+    /// e.g. "FailedScheduling", "BackOff", "Unhealthy".
+    pub reason: String,
+    /// Occurrences, counting each event's repeat `count`.
+    pub count: u32,
+    /// RFC3339 timestamp of the most recent event.
+    pub last_seen: String,
+    pub last_message: Option<String>,
+    /// "<Kind> <namespace>/<name>" of the most recent event's object.
+    pub last_object: Option<String>,
+}
+
+/// `run_kubectl` result in capture mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KubectlCapture {
+    /// After any supported `| grep` / `| tail` / … filter.
+    pub stdout: String,
+    pub stderr: String,
+    /// None when kubectl was killed by a signal.
+    pub exit_code: Option<i32>,
+}
+
+/// Result of `effective_target` — where an untargeted command will run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveTarget {
+    /// Context name, or "in-cluster".
+    pub context: String,
+    /// The namespace `list_pods` uses; None when no default namespace is set.
+    pub namespace: Option<String>,
+    /// Empty in-cluster.
+    pub source_file: String,
+    pub server_url: Option<String>,
 }
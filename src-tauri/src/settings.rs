@@ -0,0 +1,374 @@
+// Persistent app settings — a small JSON file in the Tauri app config dir.
+// Loaded once at startup into the `AppSettings` managed state. Every field
+// has a serde default, so files written by older versions keep loading.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// Namespace used when a command's `namespace` argument is omitted.
+    pub default_namespace: Option<String>,
+    /// Name prefixes hidden by `list_namespaces` when `include_system` is
+    /// false. None means `DEFAULT_SYSTEM_NAMESPACE_PREFIXES`.
+    pub system_namespace_prefixes: Option<Vec<String>>,
+    /// Extra flags put before the subcommand of every kubectl invocation,
+    /// e.g. `--request-timeout=10s` or `-v=6`.
+    pub kubectl_global_args: Vec<String>,
+    /// Label key prefixes kept in `list_pods` summaries; `get_pod` always has
+    /// every label. None means `DEFAULT_SUMMARY_LABEL_PREFIXES`; `[""]` keeps all.
+    pub summary_label_prefixes: Option<Vec<String>>,
+    /// Latency cut-offs used by `check_cluster_health` and the dashboard.
+    pub health_thresholds: HealthThresholds,
+    /// Blocks every command that changes the cluster or kubeconfig files.
+    /// `CLUSTEROPS_READONLY=1` forces it on regardless of this flag.
+    /// A guard against accidents, not a security boundary: `update_settings`
+    /// can turn the flag off, but not the environment variable.
+    pub read_only: bool,
+    /// Image substring → shell used by `exec_into_pod`, e.g.
+    /// `{"distroless": "/busybox/sh"}`. The longest matching substring wins;
+    /// unmatched images get `DEFAULT_EXEC_SHELL`.
+    pub exec_shells: BTreeMap<String, String>,
+    /// Bytes read from the exec PTY per read call. None means
+    /// `DEFAULT_PTY_READ_BUFFER`; must lie within `PTY_READ_BUFFER_RANGE`.
+    pub pty_read_buffer: Option<usize>,
+    /// Restarts per hour at which `container_restart_velocity` flags a
+    /// container as hot. None means `DEFAULT_HOT_RESTARTS_PER_HOUR`.
+    pub hot_restarts_per_hour: Option<f64>,
+    /// Extra directories scanned for kubeconfig files alongside `~/.kube`,
+    /// e.g. `/etc/kubeconfigs`. Added with `add_kubeconfig_search_path`.
+    pub kubeconfig_search_paths: Vec<String>,
+    /// Longest log line emitted as one `pod-log-line`; longer lines are split
+    /// (see `get_pod_logs`). None means `DEFAULT_MAX_LOG_LINE_BYTES`; must lie
+    /// within `MAX_LOG_LINE_BYTES_RANGE`.
+    pub max_log_line_bytes: Option<usize>,
+    /// Cap on the API requests bulk commands (`clusters_dashboard`,
+    /// `check_cluster_health`, …) have in flight at once, to stay under API
+    /// server rate limits. None means `DEFAULT_MAX_CONCURRENT_REQUESTS`.
+    pub max_concurrent_requests: Option<usize>,
+}
+
+pub const DEFAULT_PTY_READ_BUFFER: usize = 1024;
+
+pub const PTY_READ_BUFFER_RANGE: std::ops::RangeInclusive<usize> = 256..=65536;
+
+pub const DEFAULT_MAX_LOG_LINE_BYTES: usize = 64 * 1024;
+
+pub const MAX_LOG_LINE_BYTES_RANGE: std::ops::RangeInclusive<usize> = 1024..=16 * 1024 * 1024;
+
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 10;
+
+pub const MAX_CONCURRENT_REQUESTS_RANGE: std::ops::RangeInclusive<usize> = 1..=100;
+
+/// One restart every ten minutes — slower than a crash loop at full backoff.
+pub const DEFAULT_HOT_RESTARTS_PER_HOUR: f64 = 6.0;
+
+/// Shell `exec_into_pod` starts when no `exec_shells` entry matches.
+pub const DEFAULT_EXEC_SHELL: &str = "/bin/sh";
+
+/// Env var that forces read-only mode, e.g. for demos on shared machines.
+const READONLY_ENV: &str = "CLUSTEROPS_READONLY";
+
+/// When a `/healthz` probe counts as "slow" and when it gives up as
+/// "unreachable". Defaults suit a LAN; raise them for VPN or satellite links.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HealthThresholds {
+    pub slow_ms: u64,
+    pub timeout_ms: u64,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self { slow_ms: 1500, timeout_ms: 5000 }
+    }
+}
+
+impl HealthThresholds {
+    /// Both must be positive and `slow_ms` below `timeout_ms`.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.slow_ms == 0 || self.slow_ms >= self.timeout_ms {
+            return Err(format!(
+                "Invalid health thresholds: slowMs ({}) must be above 0 and below timeoutMs ({})",
+                self.slow_ms, self.timeout_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub const DEFAULT_SYSTEM_NAMESPACE_PREFIXES: &[&str] = &["kube-", "openshift-"];
+
+pub const DEFAULT_SUMMARY_LABEL_PREFIXES: &[&str] =
+    &["app", "component", "version", "app.kubernetes.io/"];
+
+/// Global kubectl flags that would change which cluster or identity a command
+/// uses, leak credentials into argv, or turn off TLS verification. The app
+/// sets target and identity itself, so these are never accepted as globals.
+const FORBIDDEN_KUBECTL_FLAGS: &[&str] = &[
+    "--kubeconfig", "--context", "--cluster", "--user", "--server", "-s",
+    "--token", "--username", "--password", "--as", "--as-group", "--as-uid",
+    "--client-certificate", "--client-key", "--certificate-authority",
+    "--insecure-skip-tls-verify", "--tls-server-name", "--namespace", "-n",
+];
+
+/// Checks `kubectl_global_args`: each entry must be a single flag (`-v=6`,
+/// `--request-timeout=10s`) and not one of `FORBIDDEN_KUBECTL_FLAGS`. A
+/// forbidden shorthand is refused with an attached value too (`-nkube-system`,
+/// `-shttps://…`), since kubectl's flag parser accepts that form.
+pub fn validate_kubectl_global_args(args: &[String]) -> Result<(), String> {
+    for arg in args {
+        if !arg.starts_with('-') || arg.chars().any(char::is_whitespace) {
+            return Err(format!("Invalid kubectl global flag '{arg}' — use --flag=value form"));
+        }
+        let flag = match arg.starts_with("--") {
+            true => arg.split('=').next().unwrap_or_default(),
+            false => arg.get(..2).unwrap_or(arg),
+        };
+        if FORBIDDEN_KUBECTL_FLAGS.contains(&flag) {
+            return Err(format!("kubectl flag '{flag}' is not allowed as a global flag"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks `exec_shells`: non-empty image substrings mapped to absolute shell
+/// paths without whitespace (the path is passed to kubectl exec as one argv).
+pub fn validate_exec_shells(shells: &BTreeMap<String, String>) -> Result<(), String> {
+    for (image, shell) in shells {
+        if image.is_empty() {
+            return Err("Exec shell mapping has an empty image pattern".to_string());
+        }
+        if !shell.starts_with('/') || shell.chars().any(char::is_whitespace) {
+            return Err(format!(
+                "Invalid exec shell '{shell}' for '{image}' — use an absolute path like /bin/bash"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The shell for `image`: the `shells` entry with the longest substring of
+/// `image`, else `DEFAULT_EXEC_SHELL`.
+pub fn exec_shell_for_image(shells: &BTreeMap<String, String>, image: &str) -> String {
+    shells
+        .iter()
+        .filter(|(pattern, _)| !pattern.is_empty() && image.contains(pattern.as_str()))
+        .max_by_key(|(pattern, _)| pattern.len())
+        .map(|(_, shell)| shell.clone())
+        .unwrap_or_else(|| DEFAULT_EXEC_SHELL.to_string())
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join("settings.json"))
+        .map_err(|e| format!("Cannot determine config directory: {e}"))
+}
+
+impl Settings {
+    /// Reads settings from disk, falling back to defaults when the file is
+    /// missing or unreadable so a bad file never blocks startup.
+    pub fn load(app: &AppHandle) -> Self {
+        settings_path(app)
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+        let path = settings_path(app)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create config directory: {e}"))?;
+        }
+        let raw = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize settings: {e}"))?;
+        std::fs::write(&path, raw).map_err(|e| format!("Failed to write settings: {e}"))
+    }
+
+    /// The configured summary label prefixes, or the defaults.
+    pub fn summary_label_prefixes(&self) -> Vec<String> {
+        match &self.summary_label_prefixes {
+            Some(prefixes) => prefixes.clone(),
+            None => DEFAULT_SUMMARY_LABEL_PREFIXES
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+
+    /// Applies a JSON merge patch (RFC 7386): objects merge key by key and
+    /// `null` resets a field to its default. The result is re-parsed, so a
+    /// patch with the wrong types is rejected without touching `self`.
+    pub fn patched(&self, patch: serde_json::Value) -> Result<Self, String> {
+        let mut current =
+            serde_json::to_value(self).map_err(|e| format!("Failed to serialize settings: {e}"))?;
+        merge_patch(&mut current, patch);
+        let patched: Self =
+            serde_json::from_value(current).map_err(|e| format!("Invalid settings: {e}"))?;
+        validate_kubectl_global_args(&patched.kubectl_global_args)?;
+        patched.health_thresholds.validate()?;
+        validate_exec_shells(&patched.exec_shells)?;
+        if let Some(n) = patched.pty_read_buffer.filter(|n| !PTY_READ_BUFFER_RANGE.contains(n)) {
+            return Err(format!(
+                "Invalid ptyReadBuffer {n}: must be between {} and {} bytes",
+                PTY_READ_BUFFER_RANGE.start(),
+                PTY_READ_BUFFER_RANGE.end()
+            ));
+        }
+        if let Some(n) =
+            patched.max_log_line_bytes.filter(|n| !MAX_LOG_LINE_BYTES_RANGE.contains(n))
+        {
+            return Err(format!(
+                "Invalid maxLogLineBytes {n}: must be between {} and {} bytes",
+                MAX_LOG_LINE_BYTES_RANGE.start(),
+                MAX_LOG_LINE_BYTES_RANGE.end()
+            ));
+        }
+        if let Some(n) = patched
+            .max_concurrent_requests
+            .filter(|n| !MAX_CONCURRENT_REQUESTS_RANGE.contains(n))
+        {
+            return Err(format!(
+                "Invalid maxConcurrentRequests {n}: must be between {} and {}",
+                MAX_CONCURRENT_REQUESTS_RANGE.start(),
+                MAX_CONCURRENT_REQUESTS_RANGE.end()
+            ));
+        }
+        if let Some(n) = patched.hot_restarts_per_hour.filter(|n| !n.is_finite() || *n <= 0.0) {
+            return Err(format!("Invalid hotRestartsPerHour {n}: must be a positive number"));
+        }
+        Ok(patched)
+    }
+
+    /// True when `read_only` is set or `CLUSTEROPS_READONLY` is "1" / "true".
+    pub fn read_only(&self) -> bool {
+        self.read_only
+            || std::env::var(READONLY_ENV)
+                .is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true"))
+    }
+
+    /// The configured PTY read buffer size, clamped to `PTY_READ_BUFFER_RANGE`
+    /// in case the file was edited by hand.
+    pub fn pty_read_buffer(&self) -> usize {
+        self.pty_read_buffer
+            .unwrap_or(DEFAULT_PTY_READ_BUFFER)
+            .clamp(*PTY_READ_BUFFER_RANGE.start(), *PTY_READ_BUFFER_RANGE.end())
+    }
+
+    /// The configured log line cap, clamped to `MAX_LOG_LINE_BYTES_RANGE` in
+    /// case the file was edited by hand.
+    pub fn max_log_line_bytes(&self) -> usize {
+        self.max_log_line_bytes
+            .unwrap_or(DEFAULT_MAX_LOG_LINE_BYTES)
+            .clamp(*MAX_LOG_LINE_BYTES_RANGE.start(), *MAX_LOG_LINE_BYTES_RANGE.end())
+    }
+
+    /// The configured request cap, clamped to `MAX_CONCURRENT_REQUESTS_RANGE`
+    /// in case the file was edited by hand.
+    pub fn max_concurrent_requests(&self) -> usize {
+        self.max_concurrent_requests
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_REQUESTS)
+            .clamp(*MAX_CONCURRENT_REQUESTS_RANGE.start(), *MAX_CONCURRENT_REQUESTS_RANGE.end())
+    }
+
+    /// The configured hot-restart threshold, or the default if unset or invalid.
+    pub fn hot_restarts_per_hour(&self) -> f64 {
+        self.hot_restarts_per_hour
+            .filter(|n| n.is_finite() && *n > 0.0)
+            .unwrap_or(DEFAULT_HOT_RESTARTS_PER_HOUR)
+    }
+
+    /// The configured health thresholds, or the defaults if a hand-edited
+    /// file holds an invalid pair.
+    pub fn health_thresholds(&self) -> HealthThresholds {
+        match self.health_thresholds.validate() {
+            Ok(()) => self.health_thresholds,
+            Err(_) => HealthThresholds::default(),
+        }
+    }
+
+    /// Resolves the namespace for a command:
+    /// 1. a non-empty `explicit` namespace wins;
+    /// 2. otherwise the saved default namespace;
+    /// 3. otherwise None (caller decides — usually "all namespaces").
+    pub fn namespace_or_default(&self, explicit: Option<String>) -> Option<String> {
+        explicit
+            .filter(|ns| !ns.is_empty())
+            .or_else(|| self.default_namespace.clone().filter(|ns| !ns.is_empty()))
+    }
+
+    /// The configured system namespace prefixes, or the defaults.
+    pub fn system_prefixes(&self) -> Vec<String> {
+        match &self.system_namespace_prefixes {
+            Some(prefixes) => prefixes.clone(),
+            None => DEFAULT_SYSTEM_NAMESPACE_PREFIXES
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+}
+
+fn merge_patch(target: &mut serde_json::Value, patch: serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(Default::default());
+    }
+    let Some(target) = target.as_object_mut() else {
+        return;
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(&key);
+        } else {
+            merge_patch(target.entry(key).or_insert(serde_json::Value::Null), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(args: &[&str]) -> Result<(), String> {
+        validate_kubectl_global_args(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn global_args_allow_harmless_flags() {
+        assert!(check(&[]).is_ok());
+        assert!(check(&["-v=6", "--request-timeout=10s", "--warnings-as-errors"]).is_ok());
+        assert!(check(&["-v6"]).is_ok());
+    }
+
+    #[test]
+    fn global_args_refuse_retargeting_flags() {
+        for arg in ["--context=prod", "--server=https://evil", "-n=kube-system", "-s"] {
+            assert!(check(&[arg]).is_err(), "{arg} should be refused");
+        }
+        assert!(check(&["-v=6", "--token=abc"]).is_err());
+    }
+
+    #[test]
+    fn global_args_refuse_attached_shorthand_values() {
+        assert!(check(&["-shttps://evil"]).is_err());
+        assert!(check(&["-nkube-system"]).is_err());
+    }
+
+    #[test]
+    fn global_args_must_be_single_flags() {
+        assert!(check(&["get"]).is_err());
+        assert!(check(&["--context prod"]).is_err());
+        assert!(check(&["-v=6\t--user=admin"]).is_err());
+    }
+}
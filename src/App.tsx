@@ -12,7 +12,7 @@ import { SecretsView } from '@/views/SecretsView'
 import { NetworkView } from '@/views/NetworkView'
 import { StorageView } from '@/views/StorageView'
 import { useUIStore } from '@/store/uiStore'
-import { useCluster } from '@/hooks/useCluster'
+import { connectContext, useCluster } from '@/hooks/useCluster'
 
 function ActiveView() {
   const { activeView } = useUIStore()
@@ -94,8 +94,8 @@ export default function App() {
         // Non-fatal: proxy will start without --kubeconfig / --context.
       }
 
-      await invoke('start_kubectl_proxy', { sourceFile, contextName })
-      // Give kubectl proxy ~400 ms to start listening on :8001.
+      await connectContext(sourceFile, contextName)
+      // Give kubectl proxy (when used) ~400 ms to start listening on :8001.
       setTimeout(() => setProxyReady(true), 400)
     }
 
@@ -6,7 +6,7 @@ import { invoke } from '@tauri-apps/api/core'
 import { Check, Copy, RefreshCw } from 'lucide-react'
 
 import { cn } from '@/lib/utils'
-import type { AIAnalysisResponse, AIInsight } from '@/types/ai'
+import type { AIAnalysisResponse, AIInsight, AIParseError } from '@/types/ai'
 import { AskAIPanel } from './AskAIPanel'
 
 // ── InsightCard ───────────────────────────────────────────────────────────────
@@ -104,30 +104,26 @@ export function AIPanel({ output, mode, analyzeKey = 0 }: AIPanelProps) {
 
     try {
       const uls = await Promise.all([
-        // Tokens stream in; we render the full JSON on ai-done
+        // Tokens stream in; we render once the backend has parsed the reply
         listen<string>('ai-stream', () => {}),
-        listen<string>('ai-done', (e) => {
+        // Structured-output providers send typed insights ahead of ai-done
+        listen<AIInsight>('ai-insight', (e) => {
+          if (!activeRef.current) return
+          setInsights((prev) => [...prev, e.payload])
+        }),
+        // The backend parses the reply; ai-done carries only the raw text
+        listen<AIAnalysisResponse>('ai-insights', (e) => {
+          if (!activeRef.current) return
+          stopListeners()
+          setStreaming(false)
+          setInsights(e.payload.insights ?? [])
+        }),
+        listen<AIParseError>('ai-parse-error', (e) => {
           if (!activeRef.current) return
           stopListeners()
           setStreaming(false)
-          const extractJson = (raw: string): string => {
-            // Try to extract from markdown code fence first
-            const fenceMatch = raw.match(/```(?:json)?\s*([\s\S]*?)```/)
-            if (fenceMatch) return fenceMatch[1].trim()
-            // Try to find raw JSON object
-            const jsonMatch = raw.match(/\{[\s\S]*\}/)
-            if (jsonMatch) return jsonMatch[0].trim()
-            // Return as-is and let JSON.parse fail with a useful error
-            return raw.trim()
-          }
-          const cleanJson = extractJson(e.payload)
-          try {
-            const parsed: AIAnalysisResponse = JSON.parse(cleanJson)
-            setInsights(parsed.insights ?? [])
-          } catch (err) {
-            console.error('[ai] parse failed. raw:', e.payload)
-            setError('Failed to parse AI response — check console for details')
-          }
+          console.error('[ai] parse failed:', e.payload.error, 'raw:', e.payload.raw)
+          setError('Failed to parse AI response — check console for details')
         }),
       ])
 
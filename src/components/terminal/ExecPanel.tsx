@@ -7,6 +7,7 @@ import '@xterm/xterm/css/xterm.css'
 
 import { useClusterStore } from '@/store/clusterStore'
 import { useUIStore } from '@/store/uiStore'
+import type { ExecOutput, SessionInvalidated } from '@/types/kubernetes'
 
 // ── Terminal theme (SPEC.md §7) ───────────────────────────────────────────────
 
@@ -68,18 +69,32 @@ export function ExecPanel() {
     // ── Event wiring ─────────────────────────────────────────────────────────
 
     let active = true
+    let nextSeq = 0
     const unlisten: (() => void)[] = []
 
     // Register listeners BEFORE invoking to guarantee no bytes are missed.
     // PTY handles echoing, prompts, and ANSI sequences — write raw output directly.
     Promise.all([
-      listen<string>('exec-output', (e) => { if (active) term.write(e.payload) }),
+      listen<ExecOutput>('exec-output', (e) => {
+        if (!active) return
+        if (e.payload.seq !== nextSeq) {
+          console.warn(`[exec] output gap: expected #${nextSeq}, got #${e.payload.seq}`)
+        }
+        nextSeq = e.payload.seq + 1
+        term.write(e.payload.data)
+      }),
       listen<null>  ('exec-done',   ()  => {
         if (!active) return
         term.writeln('\r\n\x1b[2m[session ended]\x1b[0m')
         // Clear terminal after 2 s so the next session starts with a blank slate.
         setTimeout(() => { if (active) term.clear() }, 2000)
       }),
+      // The backend killed the shell because the active context changed.
+      listen<SessionInvalidated>('session-invalidated', (e) => {
+        if (!active || !e.payload.sessions.includes('exec')) return
+        term.options.disableStdin = true
+        term.writeln(`\r\n\x1b[33m[${e.payload.reason} — session closed]\x1b[0m`)
+      }),
     ]).then((fns) => {
       if (!active) { fns.forEach((f) => f()); return }
       unlisten.push(...fns)
@@ -89,6 +104,9 @@ export function ExecPanel() {
         namespace:   selectedPod.namespace,
         sourceFile:  activeContext.sourceFile,
         contextName: activeContext.contextName,
+      }).then(() => {
+        // PTY opens at 80x24 — sync it to the fitted terminal size.
+        invoke('resize_pty', { rows: term.rows, cols: term.cols }).catch(() => {})
       }).catch((err: unknown) => {
         console.error('[exec] invoke failed:', err)
         if (active) term.writeln(`\r\n\x1b[31mError: ${String(err)}\x1b[0m`)
@@ -100,11 +118,17 @@ export function ExecPanel() {
       invoke('send_exec_input', { input: data }).catch(() => {})
     })
 
+    // Keep the PTY size in step with the fitted xterm.js viewport.
+    const onResize = term.onResize(({ rows, cols }) => {
+      invoke('resize_pty', { rows, cols }).catch(() => {})
+    })
+
     // ── Cleanup ───────────────────────────────────────────────────────────────
 
     return () => {
       active = false
       onData.dispose()
+      onResize.dispose()
       ro.disconnect()
       unlisten.forEach((f) => f())
       term.dispose()
@@ -302,7 +302,7 @@ export function OutputPanel() {
           if (!active) return
           term.writeln(`${RED}${e.payload}${RESET}`)
         }),
-        listen<null>('pod-log-done', () => {
+        listen<number>('pod-log-done', () => {
           if (!active) return
           setIsStreaming(false)
           outputForAIRef.current = logBufferRef.current.join('\n')
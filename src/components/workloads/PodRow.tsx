@@ -67,7 +67,12 @@ export function PodRow({ pod, isSelected, onSelect, onDescribe, onLogs, onExec,
       >
         {pod.restarts}
       </td>
-      <td className="px-3 py-0 text-xs font-mono text-text-muted">{pod.age}</td>
+      <td
+        className="px-3 py-0 text-xs font-mono text-text-muted"
+        title={pod.createdAt && `${new Date(pod.createdAt).toLocaleString()} (${pod.createdAt})`}
+      >
+        {pod.age}
+      </td>
       <td className="px-3 py-0 text-xs font-mono text-text-muted">{pod.cpu}</td>
       <td className="px-3 py-0 text-xs font-mono text-text-muted">{pod.memory}</td>
       <td className="px-3 py-0 text-xs font-mono text-text-muted truncate max-w-[140px]">
@@ -46,7 +46,8 @@ function SortIcon({ col, sortKey, sortDir }: SortIconProps) {
 // ── Component ─────────────────────────────────────────────────────────────────
 
 export function PodTable() {
-  const { data: pods, isLoading, error } = usePods()
+  const { data, isLoading, error } = usePods()
+  const pods = data?.pods
   const { selectedPod, setSelectedPod, openOutputPanel, incrementExecSessionKey } = useUIStore()
   // Read activeNamespace directly so the client-side filter is applied
   // immediately — including while keepPreviousData is serving the old list.
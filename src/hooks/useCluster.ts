@@ -1,5 +1,6 @@
 import { useEffect } from 'react'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { useClusterStore } from '@/store/clusterStore'
 import { useNamespaceStore } from '@/store/namespaceStore'
 import { queryClient } from '@/lib/queryClient'
@@ -18,6 +19,28 @@ async function loadNamespaces() {
   setActiveNamespace(null)
 }
 
+// ── Connection ─────────────────────────────────────────────────────────────────
+
+/**
+ * Points list/namespace queries at a context. Token and client-cert contexts
+ * talk to the API server directly; only contexts that need an exec credential
+ * plugin (or legacy auth-provider) go through kubectl proxy.
+ */
+export async function connectContext(sourceFile?: string, contextName?: string) {
+  // Abandon a proxy still starting for the previously selected context.
+  await invoke('cancel_proxy_start').catch(() => {})
+  if (sourceFile && contextName) {
+    const mode = await invoke<'direct' | 'proxy'>('use_direct_client', { sourceFile, contextName })
+      .catch(() => 'proxy' as const)
+    if (mode === 'direct') {
+      await invoke('stop_kubectl_proxy').catch(() => {})
+      return
+    }
+  }
+  await invoke('use_proxy_client')
+  await invoke('start_kubectl_proxy', { sourceFile, contextName, insecureSkipTls: false })
+}
+
 // ── useCluster ─────────────────────────────────────────────────────────────────
 
 export function useCluster() {
@@ -25,7 +48,16 @@ export function useCluster() {
 
   useEffect(() => {
     async function init() {
-      const contexts = await invoke<KubeContext[]>('get_kubeconfig_contexts')
+      // Render contexts as each kubeconfig file is parsed; the final list
+      // (with fully resolved server URLs) replaces them.
+      const found: KubeContext[] = []
+      const unlistenFound = await listen<KubeContext>('context-found', (e) => {
+        found.push(e.payload)
+        setAvailableContexts([...found])
+        if (e.payload.isActive) setActiveContext(e.payload)
+      })
+      const contexts = await invoke<KubeContext[]>('stream_kubeconfig_contexts')
+        .finally(unlistenFound)
       setAvailableContexts(contexts)
 
       const active = contexts.find((c) => c.isActive)
@@ -37,7 +69,7 @@ export function useCluster() {
           setHealth(ctx.displayName, 'unknown')
           continue
         }
-        invoke<string>('check_cluster_health', { serverUrl: ctx.serverUrl })
+        invoke<string>('check_cluster_health', { serverUrl: ctx.serverUrl, insecure: false })
           .then((h) => setHealth(ctx.displayName, h as ClusterHealth))
           .catch(() => setHealth(ctx.displayName, 'unreachable'))
       }
@@ -58,25 +90,26 @@ export async function switchClusterContext(
   setHealth: (name: string, health: ClusterHealth) => void,
 ) {
   // Persist the selection to the specific source file that owns this context.
+  // In read-only mode the kubeconfig stays untouched but the app still switches.
   await invoke('set_active_context', {
     contextName: ctx.contextName,
     sourceFile: ctx.sourceFile,
+  }).catch((e) => {
+    if (!String(e).startsWith('ReadOnly:')) throw e
   })
   setActiveContext(ctx)
 
   // Health check keyed by displayName (unique per cluster).
   if (ctx.serverUrl) {
     setHealth(ctx.displayName, 'unknown')
-    invoke<string>('check_cluster_health', { serverUrl: ctx.serverUrl })
+    invoke<string>('check_cluster_health', { serverUrl: ctx.serverUrl, insecure: false })
       .then((h) => setHealth(ctx.displayName, h as ClusterHealth))
       .catch(() => setHealth(ctx.displayName, 'unreachable'))
   }
 
-  // Restart proxy with the single kubeconfig file and exact context name.
-  await invoke('start_kubectl_proxy', {
-    sourceFile: ctx.sourceFile,
-    contextName: ctx.contextName,
-  })
+  // Direct client when the context allows it, else restart the proxy with the
+  // single kubeconfig file and exact context name.
+  await connectContext(ctx.sourceFile, ctx.contextName)
 
   await loadNamespaces()
   queryClient.invalidateQueries({ queryKey: ['pods'] })
@@ -14,3 +14,9 @@ export interface AIAnalysisResponse {
 }
 
 export type AIAnalysisMode = 'describe' | 'logs' | 'exec' | 'command' | 'security' | 'network-scan' | 'rbac-scan' | 'namespace-scan' | 'node-scan'
+
+/** `ai-parse-error` payload — the reply was not a valid AIAnalysisResponse. */
+export interface AIParseError {
+  error: string
+  raw: string
+}
@@ -22,14 +22,372 @@ export interface KubeContext {
 export interface PodSummary {
   name: string
   namespace: string
+  /** Pass as `uid` to describe / logs to refuse a recreated namesake. */
+  uid: string
   status: string
   ready: string
   restarts: number
   age: string
+  /** RFC3339 creation time, e.g. "2024-05-01T09:30:00+00:00". */
+  createdAt?: string
   cpu: string
   memory: string
   node: string
   labels: Record<string, string>
+  containers: ContainerStatusSummary[]
+}
+
+export interface ContainerStatusSummary {
+  name: string
+  ready: boolean
+  restarts: number
+  state: 'running' | 'waiting' | 'terminated' | 'unknown'
+  /** Waiting / terminated reason, e.g. CrashLoopBackOff or OOMKilled */
+  reason?: string
+}
+
+export interface PodList {
+  pods: PodSummary[]
+  metricsAvailable: boolean
+}
+
+export interface ContainerInfo {
+  name: string
+  /** True for entries from spec.initContainers */
+  isInit: boolean
+}
+
+export type AuthType = 'token' | 'exec' | 'client-cert' | 'oidc' | 'none'
+
+export interface AuthRequirements {
+  authType: AuthType
+  /** Exec credential plugin command, e.g. "aws" or "gke-gcloud-auth-plugin" */
+  execCommand?: string
+  /** Whether execCommand resolves on PATH */
+  execCommandFound: boolean
+}
+
+export interface ConnectionDetails {
+  server?: string
+  /** kubeconfig proxy-url; ignored by check_cluster_health */
+  proxyUrl?: string
+  insecureSkipTls: boolean
+  /** CA embedded as certificate-authority-data */
+  hasCaData: boolean
+  /** certificate-authority file path */
+  caFile?: string
+}
+
+export interface VersionSkew {
+  /** kubectl gitVersion, e.g. "v1.30.2" */
+  client: string
+  /** API server gitVersion */
+  server: string
+  /** False when client and server minor versions differ by more than one */
+  skewOk: boolean
+}
+
+export type ContainerStateName = 'running' | 'waiting' | 'terminated' | 'unknown'
+
+export interface ContainerDetail {
+  name: string
+  image: string
+  state: ContainerStateName
+  /** Waiting/terminated reason, e.g. "CrashLoopBackOff" or "OOMKilled" */
+  reason?: string
+  ready: boolean
+  restartCount: number
+  /** Reason the previous instance terminated — why it restarted */
+  lastTerminationReason?: string
+}
+
+export interface PodCondition {
+  type: string
+  status: string
+  reason?: string
+  message?: string
+}
+
+export interface OwnerRef {
+  kind: string
+  name: string
+}
+
+export interface PodDetail {
+  name: string
+  namespace: string
+  uid: string
+  status: string
+  node: string
+  podIp?: string
+  /** RFC3339 timestamp of when the kubelet started the pod */
+  startTime?: string
+  qosClass?: string
+  containers: ContainerDetail[]
+  initContainers: ContainerDetail[]
+  conditions: PodCondition[]
+  ownerReferences: OwnerRef[]
+  labels: Record<string, string>
+}
+
+export interface ContainerTermination {
+  exitCode: number
+  reason?: string
+  signal?: number
+  /** RFC3339 */
+  startedAt?: string
+  /** RFC3339 */
+  finishedAt?: string
+}
+
+export interface NamespacePodCounts {
+  counts: Record<string, number>
+  /** namespace → status → count; empty unless byStatus was requested. */
+  byStatus: Record<string, Record<string, number>>
+  total: number
+}
+
+/** snapshot_cluster file contents, as returned by load_snapshot. */
+export interface ClusterSnapshot {
+  version: number
+  /** RFC3339. */
+  takenAt: string
+  contextName: string
+  sourceFile: string
+  serverUrl?: string
+  pods: PodList
+  /** Raw Deployment objects; only when requested. */
+  deployments?: Record<string, unknown>[]
+  /** Set when deployments were requested but couldn't be listed. */
+  deploymentsError?: string
+  /** Raw Node objects; only when requested. */
+  nodes?: Record<string, unknown>[]
+  /** Set when nodes were requested but couldn't be listed (e.g. forbidden). */
+  nodesError?: string
+}
+
+export interface RolloutProgress {
+  phase: 'pending' | 'progressing' | 'complete' | 'paused' | 'stalled'
+  updated: number
+  available: number
+  total: number
+  message: string
+}
+
+/** One row of namespace_contents_summary. */
+export interface KindCount {
+  kind: 'pods' | 'deployments' | 'statefulsets' | 'services' | 'persistentvolumeclaims' | 'secrets'
+  /** Unset when listing failed (e.g. forbidden) — see error. */
+  count?: number
+  error?: string
+}
+
+export interface PodTemplateDrift {
+  owner: OwnerRef
+  drifted: boolean
+  /** "  " unchanged, "- " template only, "+ " pod only */
+  diff: string[]
+}
+
+export interface FinishedPod {
+  name: string
+  namespace: string
+  /** e.g. "Completed", "Error" */
+  status: string
+  /** Time since the pod finished, e.g. "3d" */
+  finished: string
+  /** Set when deleting this pod failed */
+  error?: string
+}
+
+export interface ContainerImage {
+  container: string
+  isInit: boolean
+  /** As requested in the spec, e.g. "nginx:latest". */
+  image: string
+  /** Resolved digest actually running; unset until pulled. */
+  imageId?: string
+  pullPolicy?: 'Always' | 'IfNotPresent' | 'Never'
+}
+
+export interface JobPodLogs {
+  name: string
+  phase: string
+  /** Phase Failed, or a container exited non-zero. */
+  failed: boolean
+  failures: ContainerFailure[]
+  /** Failing containers' logs; only set when failed. */
+  logs?: string
+}
+
+export interface ContainerFailure {
+  container: string
+  exitCode: number
+  reason?: string
+  /** Failure is from the previous run (container restarted in place). */
+  previous: boolean
+}
+
+export interface RestartVelocity {
+  container: string
+  restartCount: number
+  /** Age of the last restart, e.g. "3m"; unset if it never restarted. */
+  lastRestart?: string
+  /** Current estimate — drops once the container is stable again. */
+  restartsPerHour: number
+  /** At or above settings.hotRestartsPerHour. */
+  hot: boolean
+}
+
+export interface ImagePullDiagnosis {
+  container: string
+  image: string
+  reason: 'ImagePullBackOff' | 'ErrImagePull'
+  /** Kubelet message, e.g. "... pull access denied ...". */
+  message?: string
+  /** The pod's imagePullSecrets. */
+  referencedSecrets: string[]
+  /** Referenced secrets that don't exist in the namespace. */
+  missingSecrets: string[]
+}
+
+export interface PodScheduling {
+  qosClass: 'Guaranteed' | 'Burstable' | 'BestEffort'
+  node?: string
+  tolerations: string[]
+  nodeSelector: Record<string, string>
+  priorityClass?: string
+  unschedulableReason?: string
+}
+
+export interface DeletePodResult {
+  deleted: boolean
+  /** Set when a controller (e.g. ReplicaSet) will likely recreate the pod */
+  ownerKind?: string
+  ownerName?: string
+}
+
+export interface DeletePreview {
+  /** Top-level controller, e.g. the Deployment behind a ReplicaSet-owned pod */
+  owner?: OwnerRef
+  willBeRecreated: boolean
+  /** True when a matching PodDisruptionBudget currently allows no disruptions */
+  evictionBlocked: boolean
+  blockingPdbs: string[]
+}
+
+export interface Prerequisites {
+  kubectlFound: boolean
+  kubectlPath?: string
+  /** Running inside a Kubernetes pod with a mounted service account */
+  inCluster: boolean
+}
+
+export interface ClusterCard {
+  displayName: string
+  contextName: string
+  sourceFile: string
+  health: ClusterHealth
+  latencyMs?: number
+  serverVersion?: string
+  podCount?: number
+}
+
+/** `pod-container-log-line` payload from stream_pod_all_container_logs. */
+export interface ContainerLogLine {
+  container: string
+  line: string
+}
+
+export interface DescribeSection {
+  name: string
+  content: string
+}
+
+export interface ProxyStatus {
+  running: boolean
+  port?: number
+  /** RFC3339 timestamp of the last successful probe. */
+  lastHealthy?: string
+}
+
+export interface KubeconfigScanProgress {
+  dir: string
+  filesDone: number
+  filesTotal: number
+}
+
+export interface ProxyRestart {
+  /** RFC3339 timestamp. */
+  at: string
+  reason: string
+}
+
+export interface ProxyDiagnostics {
+  restarts: ProxyRestart[]
+  uptimeSecs?: number
+  recentOutput: string[]
+}
+
+/** `proxy-exited` payload — the proxy died without being stopped or replaced. */
+export interface ProxyExited {
+  /** Unset when killed by a signal. */
+  exitCode?: number
+  status: string
+  recentOutput: string[]
+}
+
+/** Cluster and user names in a kubeconfig file that no context references. */
+export interface OrphanedKubeconfigEntries {
+  clusters: string[]
+  users: string[]
+}
+
+/** `session-invalidated` payload — sessions ended by a context switch. */
+export interface SessionInvalidated {
+  reason: string
+  sessions: ('exec' | 'logs' | 'namespace-watch' | 'rollout')[]
+}
+
+export interface TopUsage {
+  name: string
+  cpu: string
+  memory: string
+}
+
+/** One row of `list_api_resources` (kubectl api-resources -o wide). */
+export interface ApiResourceInfo {
+  /** Plural name, e.g. "deployments". */
+  name: string
+  shortnames: string[]
+  /** "v1" or "group/version", e.g. "apps/v1". */
+  apiVersion: string
+  namespaced: boolean
+  kind: string
+  verbs: string[]
+}
+
+export interface NamespaceInfo {
+  name: string
+  phase: 'Active' | 'Terminating'
+}
+
+export interface ContextReachability {
+  name: string
+  cluster: string
+  reachable: ClusterHealth
+}
+
+export interface KubeconfigTest {
+  contexts: ContextReachability[]
+}
+
+export interface KubeconfigFile {
+  path: string
+  contextCount: number
+  contexts: string[]
+  isValid: boolean
+  parseError?: string
 }
 
 export type PodStatus =
@@ -41,3 +399,80 @@ export type PodStatus =
   | 'Error'
   | 'Completed'
   | 'Unknown'
+
+export interface WorkloadRef {
+  kind: 'Deployment' | 'Pod'
+  name: string
+}
+
+export interface ReplicaDiff {
+  kind: string
+  name: string
+  replicasA: number
+  replicasB: number
+}
+
+export interface NamespaceDiff {
+  namespace: string
+  onlyInA: WorkloadRef[]
+  onlyInB: WorkloadRef[]
+  replicaDifferences: ReplicaDiff[]
+}
+
+export interface ContextServer {
+  contextName: string
+  sourceFile: string
+  serverUrl?: string
+}
+
+export interface SupportBundle {
+  appVersion: string
+  os: string
+  arch: string
+  wsl: boolean
+  inCluster: boolean
+  kubectlPath?: string
+  kubectlVersion?: string
+  kubeconfigFiles: KubeconfigFile[]
+  contexts: ContextServer[]
+  proxyRunning: boolean
+  /** RFC3339 */
+  proxyLastHealthy?: string
+  proxyOutput: string[]
+}
+
+/** Payload of the exec-output event. */
+export interface ExecOutput {
+  /** 0-based, incremented per event within a session */
+  seq: number
+  /** Byte offset of data within the session's output */
+  offset: number
+  data: string
+}
+
+export interface WarningSummary {
+  reason: string
+  count: number
+  /** RFC3339 */
+  lastSeen: string
+  lastMessage?: string
+  /** "<Kind> <namespace>/<name>" */
+  lastObject?: string
+}
+
+/** run_kubectl result with capture: true */
+export interface KubectlCapture {
+  stdout: string
+  stderr: string
+  exitCode?: number
+}
+
+export interface EffectiveTarget {
+  /** Context name, or "in-cluster" */
+  context: string
+  /** What list_pods uses; unset when no default namespace is set */
+  namespace?: string
+  /** Empty in-cluster */
+  sourceFile: string
+  serverUrl?: string
+}
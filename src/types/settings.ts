@@ -0,0 +1,40 @@
+// Persisted app preferences — mirrors `Settings` in src-tauri/src/settings.rs.
+// Read with `get_settings`; change with `update_settings(patch)`.
+
+export interface AppSettings {
+  /** Namespace used when a command is called without one. */
+  defaultNamespace?: string
+  /** Prefixes hidden by list_namespaces({ includeSystem: false }).
+   *  Unset means the defaults: "kube-", "openshift-". */
+  systemNamespacePrefixes?: string[]
+  /** Flags put before every kubectl subcommand, e.g. ["--request-timeout=10s", "-v=6"]. */
+  kubectlGlobalArgs: string[]
+  /** Label key prefixes kept in list_pods summaries. Unset means the defaults
+   *  ("app", "component", "version", "app.kubernetes.io/"); [""] keeps all. */
+  summaryLabelPrefixes?: string[]
+  /** check_cluster_health latency cut-offs; slowMs must be below timeoutMs. */
+  healthThresholds: HealthThresholds
+  /** Blocks mutating commands; CLUSTEROPS_READONLY=1 forces it on. Guards against
+   *  accidents only — update_settings can clear it, so lock with the env var. */
+  readOnly: boolean
+  /** Image substring → shell for exec_into_pod, e.g. { distroless: "/busybox/sh" }.
+   *  Longest match wins; unmatched images use /bin/sh. */
+  execShells: Record<string, string>
+  /** Bytes per exec PTY read, 256–65536. Unset means 1024. */
+  ptyReadBuffer?: number
+  /** container_restart_velocity flags containers at or above this rate. Unset means 6. */
+  hotRestartsPerHour?: number
+  /** Directories scanned for kubeconfig files besides ~/.kube; every file in them is tried. */
+  kubeconfigSearchPaths: string[]
+  /** Log lines longer than this (bytes, 1024–16 MiB) are split. Unset means 64 KiB. */
+  maxLogLineBytes?: number
+  /** Parallel API requests allowed for bulk commands, 1–100. Unset means 10. */
+  maxConcurrentRequests?: number
+}
+
+export interface HealthThresholds {
+  /** Slower responses are "slow". Default 1500. */
+  slowMs: number
+  /** Longer requests are "unreachable". Default 5000. */
+  timeoutMs: number
+}